@@ -0,0 +1,54 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use ready_set_boole::boolean_evaluation::ASTNode;
+use ready_set_boole::negation_normal_form::{nnf_tree, tree_to_almost_nnf};
+use ready_set_boole::symbol_table::{build_named_ast, Symbol, SymbolTable};
+
+fn rewrite_nnf(mut tree: ASTNode<Symbol>) -> ASTNode<Symbol> {
+    let mut modified = true;
+    while modified {
+        modified = false;
+        tree_to_almost_nnf(&mut tree, &mut modified);
+    }
+    tree
+}
+
+fn negated_chain(depth: usize) -> ASTNode<Symbol> {
+    let mut formula = "x0".to_string();
+    for i in 1..depth {
+        formula.push_str(&format!(" x{}{}!", i, if i % 2 == 0 { '|' } else { '&' }));
+    }
+    build_named_ast(&formula, &mut SymbolTable::new()).unwrap()
+}
+
+fn implication_chain(depth: usize) -> ASTNode<Symbol> {
+    let mut formula = "x0".to_string();
+    for i in 1..depth {
+        formula.push_str(&format!(" x{}>!", i));
+    }
+    build_named_ast(&formula, &mut SymbolTable::new()).unwrap()
+}
+
+fn bench_nnf(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nnf");
+    for depth in [64, 256, 1024] {
+        for (name, tree) in [
+            ("negated_chain", negated_chain(depth)),
+            ("implication_chain", implication_chain(depth)),
+        ] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}/rewrite", name), depth),
+                &tree,
+                |b, tree| b.iter_batched(|| tree.clone(), rewrite_nnf, BatchSize::SmallInput),
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}/single_pass", name), depth),
+                &tree,
+                |b, tree| b.iter_batched(|| tree.clone(), nnf_tree, BatchSize::SmallInput),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_nnf);
+criterion_main!(benches);
@@ -0,0 +1,375 @@
+use crate::boolean_evaluation::{ASTNode, Atom, ParseError};
+use crate::symbol_table::{build_named_ast, Symbol, SymbolTable};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Bdd(usize);
+
+impl Bdd {
+    pub const FALSE: Bdd = Bdd(0);
+    pub const TRUE: Bdd = Bdd(1);
+
+    pub fn is_constant(self) -> bool {
+        self.0 < 2
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Node {
+    var: usize,
+    low: Bdd,
+    high: Bdd,
+}
+
+pub struct BddManager {
+    nodes: Vec<Node>,
+    unique: HashMap<(usize, Bdd, Bdd), Bdd>,
+    computed: HashMap<(char, Bdd, Bdd), Bdd>,
+    order: Vec<usize>,
+    level: Vec<Option<usize>>,
+}
+
+impl Default for BddManager {
+    fn default() -> Self {
+        let terminal = Node {
+            var: usize::MAX,
+            low: Bdd::FALSE,
+            high: Bdd::FALSE,
+        };
+        BddManager {
+            nodes: vec![terminal, terminal],
+            unique: HashMap::new(),
+            computed: HashMap::new(),
+            order: Vec::new(),
+            level: Vec::new(),
+        }
+    }
+}
+
+enum Task<T> {
+    Visit(T),
+    Combine(T, usize),
+}
+
+enum Step<'a, V: Default> {
+    Visit(&'a ASTNode<V>),
+    Combine(char, bool),
+}
+
+fn shift(count: u128, by: usize) -> Option<u128> {
+    if count == 0 {
+        Some(0)
+    } else {
+        (by <= count.leading_zeros() as usize).then(|| count << by)
+    }
+}
+
+fn apply_bool(operator: char, a: bool, b: bool) -> bool {
+    match operator {
+        '&' => a & b,
+        '|' => a | b,
+        '^' => a ^ b,
+        '>' => !a | b,
+        _ => a == b,
+    }
+}
+
+impl BddManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_order(order: &[usize]) -> Self {
+        let mut manager = Self::default();
+        for var in order {
+            manager.level_of(*var);
+        }
+        manager
+    }
+
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.order.len()
+    }
+
+    fn level_of(&mut self, var: usize) -> usize {
+        if self.level.len() <= var {
+            self.level.resize(var + 1, None);
+        }
+        if let Some(level) = self.level[var] {
+            return level;
+        }
+        self.order.push(var);
+        self.level[var] = Some(self.order.len() - 1);
+        self.order.len() - 1
+    }
+
+    fn level(&self, f: Bdd) -> usize {
+        if f.is_constant() {
+            return usize::MAX;
+        }
+        self.level[self.nodes[f.0].var].unwrap()
+    }
+
+    fn mk(&mut self, var: usize, low: Bdd, high: Bdd) -> Bdd {
+        if low == high {
+            return low;
+        }
+        if let Some(node) = self.unique.get(&(var, low, high)) {
+            return *node;
+        }
+        self.nodes.push(Node { var, low, high });
+        let node = Bdd(self.nodes.len() - 1);
+        self.unique.insert((var, low, high), node);
+        node
+    }
+
+    pub fn var(&mut self, var: usize) -> Bdd {
+        self.level_of(var);
+        self.mk(var, Bdd::FALSE, Bdd::TRUE)
+    }
+
+    pub fn constant(&self, value: bool) -> Bdd {
+        if value {
+            Bdd::TRUE
+        } else {
+            Bdd::FALSE
+        }
+    }
+
+    fn cofactors(&self, f: Bdd, level: usize) -> (Bdd, Bdd) {
+        if self.level(f) == level {
+            (self.nodes[f.0].low, self.nodes[f.0].high)
+        } else {
+            (f, f)
+        }
+    }
+
+    pub fn not(&mut self, f: Bdd) -> Bdd {
+        self.apply('^', f, Bdd::TRUE)
+    }
+
+    fn terminal(&self, operator: char, f: Bdd, g: Bdd) -> Option<Bdd> {
+        if f.is_constant() && g.is_constant() {
+            return Some(self.constant(apply_bool(operator, f == Bdd::TRUE, g == Bdd::TRUE)));
+        }
+        match (operator, f, g) {
+            ('&', Bdd::FALSE, _) | ('&', _, Bdd::FALSE) => return Some(Bdd::FALSE),
+            ('&', Bdd::TRUE, x) | ('&', x, Bdd::TRUE) => return Some(x),
+            ('|', Bdd::TRUE, _) | ('|', _, Bdd::TRUE) => return Some(Bdd::TRUE),
+            ('|', Bdd::FALSE, x) | ('|', x, Bdd::FALSE) => return Some(x),
+            ('^', Bdd::FALSE, x) | ('^', x, Bdd::FALSE) => return Some(x),
+            ('>', Bdd::FALSE, _) | ('>', _, Bdd::TRUE) => return Some(Bdd::TRUE),
+            ('>', Bdd::TRUE, x) => return Some(x),
+            ('=', Bdd::TRUE, x) | ('=', x, Bdd::TRUE) => return Some(x),
+            _ => {}
+        }
+        if f == g {
+            return Some(match operator {
+                '&' | '|' => f,
+                '^' => Bdd::FALSE,
+                _ => Bdd::TRUE,
+            });
+        }
+        None
+    }
+
+    pub fn apply(&mut self, operator: char, f: Bdd, g: Bdd) -> Bdd {
+        if operator == '!' {
+            return self.not(g);
+        }
+        let key = |f: Bdd, g: Bdd| {
+            if operator != '>' && g < f {
+                (operator, g, f)
+            } else {
+                (operator, f, g)
+            }
+        };
+
+        let mut tasks = vec![Task::Visit((f, g))];
+        let mut results = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit((f, g)) => {
+                    if let Some(res) = self.terminal(operator, f, g) {
+                        results.push(res);
+                    } else if let Some(res) = self.computed.get(&key(f, g)) {
+                        results.push(*res);
+                    } else {
+                        let level = self.level(f).min(self.level(g));
+                        let (f0, f1) = self.cofactors(f, level);
+                        let (g0, g1) = self.cofactors(g, level);
+                        tasks.push(Task::Combine((f, g), self.order[level]));
+                        tasks.push(Task::Visit((f1, g1)));
+                        tasks.push(Task::Visit((f0, g0)));
+                    }
+                }
+                Task::Combine((f, g), var) => {
+                    let high = results.pop().unwrap();
+                    let low = results.pop().unwrap();
+                    let res = self.mk(var, low, high);
+                    self.computed.insert(key(f, g), res);
+                    results.push(res);
+                }
+            }
+        }
+        results.pop().unwrap()
+    }
+
+    pub fn restrict(&mut self, f: Bdd, var: usize, value: bool) -> Bdd {
+        let level = self.level_of(var);
+        let mut cache: HashMap<Bdd, Bdd> = HashMap::new();
+        let mut tasks = vec![Task::Visit(f)];
+        let mut results = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(f) if self.level(f) > level => results.push(f),
+                Task::Visit(f) if cache.contains_key(&f) => results.push(cache[&f]),
+                Task::Visit(f) if self.level(f) == level => {
+                    let Node { low, high, .. } = self.nodes[f.0];
+                    let res = if value { high } else { low };
+                    cache.insert(f, res);
+                    results.push(res);
+                }
+                Task::Visit(f) => {
+                    let Node { var, low, high } = self.nodes[f.0];
+                    tasks.push(Task::Combine(f, var));
+                    tasks.push(Task::Visit(high));
+                    tasks.push(Task::Visit(low));
+                }
+                Task::Combine(f, var) => {
+                    let high = results.pop().unwrap();
+                    let low = results.pop().unwrap();
+                    let res = self.mk(var, low, high);
+                    cache.insert(f, res);
+                    results.push(res);
+                }
+            }
+        }
+        results.pop().unwrap()
+    }
+
+    fn quantify(&mut self, mut f: Bdd, vars: &[usize], operator: char) -> Bdd {
+        for var in vars {
+            let low = self.restrict(f, *var, false);
+            let high = self.restrict(f, *var, true);
+            f = self.apply(operator, low, high);
+        }
+        f
+    }
+
+    pub fn exists(&mut self, f: Bdd, vars: &[usize]) -> Bdd {
+        self.quantify(f, vars, '|')
+    }
+
+    pub fn forall(&mut self, f: Bdd, vars: &[usize]) -> Bdd {
+        self.quantify(f, vars, '&')
+    }
+
+    pub fn eval(&self, f: Bdd, assignment: &impl Fn(usize) -> bool) -> bool {
+        let mut node = f;
+        while !node.is_constant() {
+            let Node { var, low, high } = self.nodes[node.0];
+            node = if assignment(var) { high } else { low };
+        }
+        node == Bdd::TRUE
+    }
+
+    pub fn model_count(&self, f: Bdd) -> Option<u128> {
+        let mut counts = HashMap::from([(Bdd::FALSE, 0), (Bdd::TRUE, 1)]);
+        let gap = |parent: usize, child: Bdd| self.level(child).min(self.num_vars()) - parent - 1;
+        let mut tasks = vec![Task::Visit(f)];
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(f) if counts.contains_key(&f) => {}
+                Task::Visit(f) => {
+                    let Node { low, high, .. } = self.nodes[f.0];
+                    tasks.push(Task::Combine(f, self.level(f)));
+                    tasks.push(Task::Visit(high));
+                    tasks.push(Task::Visit(low));
+                }
+                Task::Combine(f, level) => {
+                    let Node { low, high, .. } = self.nodes[f.0];
+                    let low = shift(counts[&low], gap(level, low))?;
+                    let high = shift(counts[&high], gap(level, high))?;
+                    counts.insert(f, low.checked_add(high)?);
+                }
+            }
+        }
+        shift(counts[&f], self.level(f).min(self.num_vars()))
+    }
+
+    pub fn size(&self, f: Bdd) -> usize {
+        let mut seen = HashSet::from([f]);
+        let mut stack = vec![f];
+        while let Some(node) = stack.pop() {
+            if node.is_constant() {
+                continue;
+            }
+            for child in [self.nodes[node.0].low, self.nodes[node.0].high] {
+                if seen.insert(child) {
+                    stack.push(child);
+                }
+            }
+        }
+        seen.iter().filter(|n| !n.is_constant()).count()
+    }
+
+    fn build<V: Atom>(&mut self, node: &ASTNode<V>, index: &impl Fn(&V) -> usize) -> Bdd {
+        let mut steps = vec![Step::Visit(node)];
+        let mut results = Vec::new();
+        while let Some(step) = steps.pop() {
+            match step {
+                Step::Visit(ASTNode::Value(v)) => results.push(match v.constant() {
+                    Some(value) => self.constant(value),
+                    None => self.var(index(v)),
+                }),
+                Step::Visit(ASTNode::Op {
+                    operator,
+                    left,
+                    right,
+                }) => {
+                    steps.push(Step::Combine(*operator, left.is_some()));
+                    if let Some(l) = left {
+                        steps.push(Step::Visit(l));
+                    }
+                    steps.push(Step::Visit(right));
+                }
+                Step::Combine(operator, binary) => {
+                    let res = if binary {
+                        let left = results.pop().unwrap();
+                        let right = results.pop().unwrap();
+                        self.apply(operator, left, right)
+                    } else {
+                        let right = results.pop().unwrap();
+                        self.not(right)
+                    };
+                    results.push(res);
+                }
+            }
+        }
+        results.pop().unwrap()
+    }
+
+    pub fn from_ast(&mut self, node: &ASTNode<char>) -> Bdd {
+        self.build(node, &|c: &char| (*c as u8 - b'A') as usize)
+    }
+
+    pub fn from_named_ast(&mut self, node: &ASTNode<Symbol>) -> Bdd {
+        self.build(node, &|s: &Symbol| match s {
+            Symbol::Var(id) => *id,
+            _ => unreachable!(),
+        })
+    }
+}
+
+pub fn bdd_equivalent(a: &str, b: &str) -> Result<bool, ParseError> {
+    let mut table = SymbolTable::new();
+    let a = build_named_ast(a, &mut table)?;
+    let b = build_named_ast(b, &mut table)?;
+    let mut manager = BddManager::new();
+    Ok(manager.from_named_ast(&a) == manager.from_named_ast(&b))
+}
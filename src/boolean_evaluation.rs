@@ -1,5 +1,10 @@
-#[derive(Clone)]
-pub enum ASTNode<V> {
+use crate::symbol_table::{build_named_ast, Symbol, SymbolTable};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::mem;
+
+#[derive(Debug)]
+pub enum ASTNode<V: Default> {
     Value(V),
     Op {
         operator: char,
@@ -8,81 +13,484 @@ pub enum ASTNode<V> {
     },
 }
 
-pub fn build_ast(formula: &str) -> Option<ASTNode<char>> {
-    let mut stack: Vec<ASTNode<char>> = Vec::new();
+enum Step<'a, V: Default> {
+    Visit(&'a ASTNode<V>),
+    Combine(char, bool),
+}
 
-    for c in formula.chars() {
-        if c == '0' || c == '1' || c >= 'A' && c <= 'Z' {
-            stack.push(ASTNode::Value(c));
-        } else {
-            if !['!', '&', '|', '^', '>', '='].contains(&c)
-                || stack.len() < 1
-                || (stack.len() < 2 && c != '!')
-            {
-                return None;
+enum OwnedStep<V: Default> {
+    Visit(ASTNode<V>),
+    Combine(char, bool),
+}
+
+fn take_children<V: Default>(node: &mut ASTNode<V>) -> Option<(Option<ASTNode<V>>, ASTNode<V>)> {
+    let ASTNode::Op { left, right, .. } = node else {
+        return None;
+    };
+    let left = left.take().map(|l| *l);
+    Some((
+        left,
+        mem::replace(&mut **right, ASTNode::Value(V::default())),
+    ))
+}
+
+fn detach_children<V: Default>(node: &mut ASTNode<V>, stack: &mut Vec<ASTNode<V>>) {
+    if let Some((left, right)) = take_children(node) {
+        for child in left.into_iter().chain([right]) {
+            if let ASTNode::Op { .. } = child {
+                stack.push(child);
             }
+        }
+    }
+}
 
-            let node = ASTNode::Op {
-                operator: c,
-                right: Box::new(stack.pop().unwrap()),
-                left: if c != '!' {
-                    Some(Box::new(stack.pop().unwrap()))
-                } else {
-                    None
-                },
-            };
-            stack.push(node)
+impl<V: Default> Drop for ASTNode<V> {
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        detach_children(self, &mut stack);
+        while let Some(mut node) = stack.pop() {
+            detach_children(&mut node, &mut stack);
         }
     }
+}
 
-    if stack.len() != 1 {
-        return None;
+impl<V: Clone + Default> Clone for ASTNode<V> {
+    fn clone(&self) -> Self {
+        self.map(&|v| v.clone())
     }
+}
 
-    return Some(stack.pop().unwrap());
+impl<V: PartialEq + Default> PartialEq for ASTNode<V> {
+    fn eq(&self, other: &Self) -> bool {
+        let mut stack = vec![(self, other)];
+        while let Some(pair) = stack.pop() {
+            match pair {
+                (ASTNode::Value(a), ASTNode::Value(b)) if a == b => {}
+                (
+                    ASTNode::Op {
+                        operator: a,
+                        left: a_left,
+                        right: a_right,
+                    },
+                    ASTNode::Op {
+                        operator: b,
+                        left: b_left,
+                        right: b_right,
+                    },
+                ) if a == b => {
+                    match (a_left, b_left) {
+                        (Some(a_left), Some(b_left)) => stack.push((a_left, b_left)),
+                        (None, None) => {}
+                        _ => return false,
+                    }
+                    stack.push((a_right, b_right));
+                }
+                _ => return false,
+            }
+        }
+        true
+    }
 }
 
-pub fn eval_node(node: &ASTNode<char>) -> Option<bool> {
-    match node {
-        ASTNode::Value(c) => match c {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    EmptyFormula,
+    StackUnderflow {
+        offset: usize,
+        symbol: char,
+    },
+    UnknownSymbol {
+        offset: usize,
+        symbol: char,
+    },
+    LeftoverOperands {
+        offset: usize,
+        symbol: char,
+        count: usize,
+    },
+    UnexpectedVariable {
+        offset: usize,
+        symbol: char,
+    },
+    UnexpectedSymbol {
+        offset: usize,
+        symbol: char,
+    },
+    UnexpectedEnd {
+        offset: usize,
+    },
+    UnclosedParenthesis {
+        offset: usize,
+    },
+    UnboundVariable {
+        name: String,
+    },
+    TooManyVariables {
+        count: usize,
+        limit: usize,
+    },
+}
+
+impl ParseError {
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            ParseError::EmptyFormula
+            | ParseError::UnboundVariable { .. }
+            | ParseError::TooManyVariables { .. } => None,
+            ParseError::StackUnderflow { offset, .. }
+            | ParseError::UnknownSymbol { offset, .. }
+            | ParseError::LeftoverOperands { offset, .. }
+            | ParseError::UnexpectedVariable { offset, .. }
+            | ParseError::UnexpectedSymbol { offset, .. }
+            | ParseError::UnexpectedEnd { offset }
+            | ParseError::UnclosedParenthesis { offset } => Some(*offset),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::EmptyFormula => write!(f, "empty formula"),
+            ParseError::StackUnderflow { offset, symbol } => {
+                write!(f, "missing operand for '{}' at offset {}", symbol, offset)
+            }
+            ParseError::UnknownSymbol { offset, symbol } => {
+                write!(f, "unknown symbol '{}' at offset {}", symbol, offset)
+            }
+            ParseError::LeftoverOperands {
+                offset,
+                symbol,
+                count,
+            } => write!(
+                f,
+                "{} operands left on the stack after '{}' at offset {}",
+                count, symbol, offset
+            ),
+            ParseError::UnexpectedVariable { offset, symbol } => write!(
+                f,
+                "variable '{}' at offset {} is not allowed here",
+                symbol, offset
+            ),
+            ParseError::UnexpectedSymbol { offset, symbol } => {
+                write!(f, "unexpected '{}' at offset {}", symbol, offset)
+            }
+            ParseError::UnexpectedEnd { offset } => {
+                write!(f, "unexpected end of formula at offset {}", offset)
+            }
+            ParseError::UnclosedParenthesis { offset } => {
+                write!(f, "parenthesis opened at offset {} is never closed", offset)
+            }
+            ParseError::UnboundVariable { name } => {
+                write!(f, "no value given for variable '{}'", name)
+            }
+            ParseError::TooManyVariables { count, limit } => write!(
+                f,
+                "{} variables is more than the {} a truth table can enumerate",
+                count, limit
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub trait Atom: Clone + Default + PartialEq {
+    fn constant(&self) -> Option<bool>;
+    fn from_constant(value: bool) -> Self;
+}
+
+impl Atom for char {
+    fn constant(&self) -> Option<bool> {
+        match self {
             '0' => Some(false),
             '1' => Some(true),
-            _ => {
-                println!("Invalid value in AST");
-                None
+            _ => None,
+        }
+    }
+
+    fn from_constant(value: bool) -> Self {
+        if value {
+            '1'
+        } else {
+            '0'
+        }
+    }
+}
+
+impl<V: Default> ASTNode<V> {
+    pub fn fold<R>(
+        &self,
+        mut leaf: impl FnMut(&V) -> R,
+        mut op: impl FnMut(char, Option<R>, R) -> R,
+    ) -> R {
+        let mut steps = vec![Step::Visit(self)];
+        let mut results = Vec::new();
+        while let Some(step) = steps.pop() {
+            match step {
+                Step::Visit(ASTNode::Value(v)) => results.push(leaf(v)),
+                Step::Visit(ASTNode::Op {
+                    operator,
+                    left,
+                    right,
+                }) => {
+                    steps.push(Step::Combine(*operator, left.is_some()));
+                    steps.push(Step::Visit(right));
+                    if let Some(l) = left {
+                        steps.push(Step::Visit(l));
+                    }
+                }
+                Step::Combine(operator, binary) => {
+                    let right = results.pop().unwrap();
+                    let left = if binary { results.pop() } else { None };
+                    results.push(op(operator, left, right));
+                }
             }
-        },
+        }
+        results.pop().unwrap()
+    }
 
-        ASTNode::Op {
-            operator,
-            left,
-            right,
-        } => match operator {
-            '&' => Some(eval_node(left.as_ref()?)? & eval_node(right)?),
-            '|' => Some(eval_node(left.as_ref()?)? | eval_node(right)?),
-            '!' => Some(!eval_node(right)?),
-            '=' => Some(eval_node(left.as_ref()?)? == eval_node(right)?),
-            '>' => Some(!eval_node(left.as_ref()?)? | eval_node(right)?),
-            '^' => Some(eval_node(left.as_ref()?)? ^ eval_node(right)?),
-            _ => {
-                println!("Invalid operator in AST");
-                None
+    pub fn rebuild(
+        self,
+        mut op: impl FnMut(char, Option<ASTNode<V>>, ASTNode<V>) -> ASTNode<V>,
+    ) -> ASTNode<V> {
+        let mut steps = vec![OwnedStep::Visit(self)];
+        let mut results = Vec::new();
+        while let Some(step) = steps.pop() {
+            match step {
+                OwnedStep::Visit(mut node) => {
+                    let operator = match &node {
+                        ASTNode::Op { operator, .. } => *operator,
+                        ASTNode::Value(_) => {
+                            results.push(node);
+                            continue;
+                        }
+                    };
+                    let (left, right) = take_children(&mut node).unwrap();
+                    steps.push(OwnedStep::Combine(operator, left.is_some()));
+                    steps.push(OwnedStep::Visit(right));
+                    if let Some(l) = left {
+                        steps.push(OwnedStep::Visit(l));
+                    }
+                }
+                OwnedStep::Combine(operator, binary) => {
+                    let right = results.pop().unwrap();
+                    let left = if binary { results.pop() } else { None };
+                    results.push(op(operator, left, right));
+                }
             }
-        },
+        }
+        results.pop().unwrap()
+    }
+
+    pub fn into_children(mut self) -> Option<(Option<ASTNode<V>>, ASTNode<V>)> {
+        take_children(&mut self)
+    }
+
+    pub fn map<W: Default>(&self, f: &impl Fn(&V) -> W) -> ASTNode<W> {
+        self.fold(
+            |v| ASTNode::Value(f(v)),
+            |operator, left, right| ASTNode::Op {
+                operator,
+                left: left.map(Box::new),
+                right: Box::new(right),
+            },
+        )
+    }
+}
+
+pub const OPERATORS: [char; 6] = ['!', '&', '|', '^', '>', '='];
+
+pub fn parse_rpn<V: Default>(
+    formula: &str,
+    leaf: impl FnMut(&[char], usize) -> Option<(V, usize)>,
+) -> Result<ASTNode<V>, ParseError> {
+    parse_rpn_with(formula, &OPERATORS, leaf)
+}
+
+pub fn parse_rpn_with<V: Default>(
+    formula: &str,
+    operators: &[char],
+    mut leaf: impl FnMut(&[char], usize) -> Option<(V, usize)>,
+) -> Result<ASTNode<V>, ParseError> {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut stack: Vec<ASTNode<V>> = Vec::new();
+    let mut last = None;
+    let mut offset = 0;
+
+    while offset < chars.len() {
+        let c = chars[offset];
+        if c.is_whitespace() {
+            offset += 1;
+            continue;
+        }
+        last = Some((offset, c));
+        if let Some((value, end)) = leaf(&chars, offset) {
+            stack.push(ASTNode::Value(value));
+            offset = end;
+            continue;
+        }
+
+        if !operators.contains(&c) {
+            return Err(ParseError::UnknownSymbol { offset, symbol: c });
+        }
+        if stack.is_empty() || (stack.len() < 2 && c != '!') {
+            return Err(ParseError::StackUnderflow { offset, symbol: c });
+        }
+
+        let node = ASTNode::Op {
+            operator: c,
+            right: Box::new(stack.pop().unwrap()),
+            left: if c != '!' {
+                Some(Box::new(stack.pop().unwrap()))
+            } else {
+                None
+            },
+        };
+        stack.push(node);
+        offset += 1;
     }
+
+    let Some((offset, symbol)) = last else {
+        return Err(ParseError::EmptyFormula);
+    };
+    if stack.len() != 1 {
+        return Err(ParseError::LeftoverOperands {
+            offset,
+            symbol,
+            count: stack.len(),
+        });
+    }
+
+    Ok(stack.pop().unwrap())
 }
 
-pub fn eval_formula(formula: &str) -> bool {
-    let tree = build_ast(formula);
+pub fn build_ast(formula: &str) -> Result<ASTNode<char>, ParseError> {
+    parse_rpn(formula, |chars, i| {
+        let c = chars[i];
+        if c == '0' || c == '1' || c.is_ascii_uppercase() {
+            Some((c, i + 1))
+        } else {
+            None
+        }
+    })
+}
 
-    if tree.is_none() {
-        println!("Error in formula");
-        return false;
+pub fn reject_symbols(
+    formula: &str,
+    forbidden: impl Fn(char) -> bool,
+    error: impl Fn(usize, char) -> ParseError,
+) -> Result<(), ParseError> {
+    match formula.chars().enumerate().find(|(_, c)| forbidden(*c)) {
+        Some((offset, symbol)) => Err(error(offset, symbol)),
+        None => Ok(()),
     }
+}
+
+pub fn eval_with<V: Default>(
+    node: &ASTNode<V>,
+    value: &impl Fn(&V) -> Option<bool>,
+) -> Option<bool> {
+    node.fold(value, |operator, left, right| {
+        let right = right?;
+        if operator == '!' {
+            return Some(!right);
+        }
+        let left = left??;
+        match operator {
+            '&' => Some(left & right),
+            '|' => Some(left | right),
+            '=' => Some(left == right),
+            '>' => Some(!left | right),
+            '^' => Some(left ^ right),
+            _ => None,
+        }
+    })
+}
+
+fn negate<V: Default>(node: ASTNode<V>) -> ASTNode<V> {
+    ASTNode::Op {
+        operator: '!',
+        left: None,
+        right: Box::new(node),
+    }
+}
+
+fn node_constant<V: Atom>(node: &ASTNode<V>) -> Option<bool> {
+    match node {
+        ASTNode::Value(v) => v.constant(),
+        _ => None,
+    }
+}
+
+pub fn fold_constants<V: Atom>(node: ASTNode<V>) -> ASTNode<V> {
+    node.rebuild(fold_op)
+}
+
+fn fold_op<V: Atom>(operator: char, left: Option<ASTNode<V>>, right: ASTNode<V>) -> ASTNode<V> {
+    let Some(left) = left else {
+        return match node_constant(&right) {
+            Some(value) => ASTNode::Value(V::from_constant(!value)),
+            None => negate(right),
+        };
+    };
+
+    match (operator, node_constant(&left), node_constant(&right)) {
+        (_, Some(_), Some(_)) => {
+            let node = ASTNode::Op {
+                operator,
+                left: Some(Box::new(left)),
+                right: Box::new(right),
+            };
+            ASTNode::Value(V::from_constant(eval_node(&node).unwrap()))
+        }
+        ('&', Some(false), _) | ('&', _, Some(false)) => ASTNode::Value(V::from_constant(false)),
+        ('|', Some(true), _) | ('|', _, Some(true)) => ASTNode::Value(V::from_constant(true)),
+        ('>', Some(false), _) | ('>', _, Some(true)) => ASTNode::Value(V::from_constant(true)),
+        ('&', Some(true), _) | ('|', Some(false), _) | ('^', Some(false), _) => right,
+        ('>', Some(true), _) | ('=', Some(true), _) => right,
+        ('&', _, Some(true)) | ('|', _, Some(false)) | ('^', _, Some(false)) => left,
+        ('=', _, Some(true)) => left,
+        ('^', Some(true), _) | ('=', Some(false), _) => negate(right),
+        ('^', _, Some(true)) | ('=', _, Some(false)) | ('>', _, Some(false)) => negate(left),
+        _ => ASTNode::Op {
+            operator,
+            left: Some(Box::new(left)),
+            right: Box::new(right),
+        },
+    }
+}
+
+pub fn eval_node<V: Atom>(node: &ASTNode<V>) -> Option<bool> {
+    eval_with(node, &|v: &V| v.constant())
+}
+
+pub fn eval_formula(formula: &str) -> Result<bool, ParseError> {
+    let tree = build_ast(formula)?;
+    reject_symbols(
+        formula,
+        |c| c.is_ascii_uppercase(),
+        |offset, symbol| ParseError::UnexpectedVariable { offset, symbol },
+    )?;
+
+    Ok(eval_node(&tree).unwrap())
+}
+
+pub fn eval_formula_with(
+    formula: &str,
+    assignment: &BTreeMap<String, bool>,
+) -> Result<bool, ParseError> {
+    let mut table = SymbolTable::new();
+    let tree = build_named_ast(formula, &mut table)?;
 
-    if let Some(result) = eval_node(&tree.unwrap()) {
-        return result;
-    } else {
-        return false;
+    if let Some(name) = table.names().find(|name| !assignment.contains_key(*name)) {
+        return Err(ParseError::UnboundVariable {
+            name: name.to_string(),
+        });
     }
+    Ok(eval_with(&tree, &|s: &Symbol| match s {
+        Symbol::Var(id) => assignment.get(table.name(*id)).copied(),
+        _ => s.constant(),
+    })
+    .unwrap())
 }
@@ -0,0 +1,155 @@
+use crate::boolean_evaluation::{ASTNode, Atom, ParseError};
+use crate::dag::{Dag, DagNode};
+use crate::symbol_table::{Symbol, SymbolTable};
+
+const LANES: [u64; 6] = [
+    0xAAAA_AAAA_AAAA_AAAA,
+    0xCCCC_CCCC_CCCC_CCCC,
+    0xF0F0_F0F0_F0F0_F0F0,
+    0xFF00_FF00_FF00_FF00,
+    0xFFFF_0000_FFFF_0000,
+    0xFFFF_FFFF_0000_0000,
+];
+
+// Programs and truth tables enumerate all 2^n rows, so n is capped well below
+// the point where the row count overflows or the table no longer fits in memory.
+pub const MAX_VARS: usize = 30;
+
+pub fn check_vars(count: usize) -> Result<(), ParseError> {
+    if count > MAX_VARS {
+        Err(ParseError::TooManyVariables {
+            count,
+            limit: MAX_VARS,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instr {
+    Const(bool),
+    Load(usize),
+    Not(usize),
+    Binary(char, usize, usize),
+}
+
+#[derive(Clone, Debug)]
+pub struct Program {
+    code: Vec<Instr>,
+    output: usize,
+    vars: usize,
+}
+
+impl Program {
+    pub fn compile(tree: &ASTNode<Symbol>, table: &SymbolTable) -> Result<Program, ParseError> {
+        check_vars(table.names().count())?;
+        let mut names: Vec<(&str, usize)> = table.names().zip(0..).collect();
+        names.sort();
+        let mut columns = vec![0; names.len()];
+        for (column, (_, id)) in names.iter().enumerate() {
+            columns[*id] = column;
+        }
+
+        let mut dag = Dag::new();
+        let output = dag.from_ast(tree).index();
+        let code = dag
+            .nodes()
+            .iter()
+            .map(|node| match *node {
+                DagNode::Value(Symbol::Var(id)) => Instr::Load(columns[id]),
+                DagNode::Value(s) => Instr::Const(s.constant() == Some(true)),
+                DagNode::Op {
+                    left: None, right, ..
+                } => Instr::Not(right.index()),
+                DagNode::Op {
+                    operator,
+                    left: Some(left),
+                    right,
+                } => Instr::Binary(operator, left.index(), right.index()),
+            })
+            .collect();
+
+        Ok(Program {
+            code,
+            output,
+            vars: names.len(),
+        })
+    }
+
+    pub fn code(&self) -> &[Instr] {
+        &self.code
+    }
+
+    pub fn vars(&self) -> usize {
+        self.vars
+    }
+
+    fn load(&self, column: usize, block: u64) -> u64 {
+        let shift = self.vars - 1 - column;
+        if shift < LANES.len() {
+            LANES[shift]
+        } else if (block >> (shift - LANES.len())) & 1 == 1 {
+            !0
+        } else {
+            0
+        }
+    }
+
+    fn run(&self, block: u64, regs: &mut [u64]) -> u64 {
+        for (i, instr) in self.code.iter().enumerate() {
+            regs[i] = match *instr {
+                Instr::Const(value) => {
+                    if value {
+                        !0
+                    } else {
+                        0
+                    }
+                }
+                Instr::Load(column) => self.load(column, block),
+                Instr::Not(a) => !regs[a],
+                Instr::Binary(operator, a, b) => {
+                    let (a, b) = (regs[a], regs[b]);
+                    match operator {
+                        '&' => a & b,
+                        '|' => a | b,
+                        '^' => a ^ b,
+                        '>' => !a | b,
+                        _ => !(a ^ b),
+                    }
+                }
+            };
+        }
+        regs[self.output]
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = u64> + '_ {
+        let rows = 1u64 << self.vars;
+        let mask = if rows < 64 { (1 << rows) - 1 } else { !0 };
+        let mut regs = vec![0; self.code.len()];
+        (0..rows.div_ceil(64)).map(move |block| self.run(block, &mut regs) & mask)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = bool> + '_ {
+        self.blocks()
+            .flat_map(|word| (0..64).map(move |lane| (word >> lane) & 1 == 1))
+            .take(1 << self.vars)
+    }
+
+    pub fn row(&self, row: u64) -> Vec<bool> {
+        (0..self.vars)
+            .map(|column| (row >> (self.vars - 1 - column)) & 1 == 1)
+            .collect()
+    }
+
+    pub fn count(&self) -> u64 {
+        self.blocks().map(|word| word.count_ones() as u64).sum()
+    }
+
+    pub fn first_model(&self) -> Option<u64> {
+        self.blocks()
+            .zip(0u64..)
+            .find(|(word, _)| *word != 0)
+            .map(|(word, block)| block * 64 + word.trailing_zeros() as u64)
+    }
+}
@@ -0,0 +1,430 @@
+use std::ops::Not;
+
+pub const MAX_VARS: usize = 1 << 31;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lit(u32);
+
+impl Lit {
+    pub fn new(var: usize, positive: bool) -> Self {
+        Lit(((var as u32) << 1) | (!positive) as u32)
+    }
+
+    pub fn var(self) -> usize {
+        (self.0 >> 1) as usize
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 & 1 == 0
+    }
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Not for Lit {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Lit(self.0 ^ 1)
+    }
+}
+
+fn lit_value(values: &[Option<bool>], lit: Lit) -> Option<bool> {
+    values[lit.var()].map(|v| v == lit.is_positive())
+}
+
+fn luby(mut i: u64) -> u64 {
+    loop {
+        let mut k = 1;
+        while (1u64 << k) - 1 < i {
+            k += 1;
+        }
+        if (1u64 << k) - 1 == i {
+            return 1 << (k - 1);
+        }
+        i -= (1u64 << (k - 1)) - 1;
+    }
+}
+
+#[derive(Default)]
+struct VarOrder {
+    heap: Vec<usize>,
+    position: Vec<Option<usize>>,
+}
+
+impl VarOrder {
+    fn contains(&self, var: usize) -> bool {
+        self.position[var].is_some()
+    }
+
+    fn push(&mut self, var: usize, activity: &[f64]) {
+        if self.position.len() <= var {
+            self.position.resize(var + 1, None);
+        }
+        if self.contains(var) {
+            return;
+        }
+        self.heap.push(var);
+        self.position[var] = Some(self.heap.len() - 1);
+        self.sift_up(self.heap.len() - 1, activity);
+    }
+
+    fn pop(&mut self, activity: &[f64]) -> Option<usize> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let top = self.heap.swap_remove(0);
+        self.position[top] = None;
+        if !self.heap.is_empty() {
+            self.position[self.heap[0]] = Some(0);
+            self.sift_down(0, activity);
+        }
+        Some(top)
+    }
+
+    fn increased(&mut self, var: usize, activity: &[f64]) {
+        if let Some(i) = self.position[var] {
+            self.sift_up(i, activity);
+        }
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.position[self.heap[i]] = Some(i);
+        self.position[self.heap[j]] = Some(j);
+    }
+
+    fn sift_up(&mut self, mut i: usize, activity: &[f64]) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if activity[self.heap[parent]] >= activity[self.heap[i]] {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize, activity: &[f64]) {
+        loop {
+            let mut best = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < self.heap.len() && activity[self.heap[child]] > activity[self.heap[best]]
+                {
+                    best = child;
+                }
+            }
+            if best == i {
+                break;
+            }
+            self.swap(i, best);
+            i = best;
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Solver {
+    clauses: Vec<Vec<Lit>>,
+    watches: Vec<Vec<usize>>,
+    values: Vec<Option<bool>>,
+    level: Vec<usize>,
+    reason: Vec<Option<usize>>,
+    phase: Vec<bool>,
+    activity: Vec<f64>,
+    var_inc: f64,
+    order: VarOrder,
+    trail: Vec<Lit>,
+    trail_lim: Vec<usize>,
+    qhead: usize,
+    inconsistent: bool,
+}
+
+impl Solver {
+    pub fn new(num_vars: usize) -> Self {
+        let mut solver = Solver {
+            var_inc: 1.0,
+            ..Default::default()
+        };
+        if num_vars > 0 {
+            solver.ensure_var(num_vars - 1);
+        }
+        solver
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.values.len()
+    }
+
+    fn ensure_var(&mut self, var: usize) {
+        while self.values.len() <= var {
+            let v = self.values.len();
+            self.values.push(None);
+            self.level.push(0);
+            self.reason.push(None);
+            self.phase.push(false);
+            self.activity.push(0.0);
+            self.watches.push(Vec::new());
+            self.watches.push(Vec::new());
+            self.order.push(v, &self.activity);
+        }
+    }
+
+    fn decision_level(&self) -> usize {
+        self.trail_lim.len()
+    }
+
+    pub fn add_clause(&mut self, lits: &[Lit]) -> bool {
+        if self.inconsistent {
+            return false;
+        }
+        self.cancel_until(0);
+
+        let mut clause = lits.to_vec();
+        clause.sort();
+        clause.dedup();
+        if let Some(max) = clause.iter().map(|l| l.var()).max() {
+            self.ensure_var(max);
+        }
+        if clause.windows(2).any(|w| w[0] == !w[1]) {
+            return true;
+        }
+        if clause
+            .iter()
+            .any(|l| lit_value(&self.values, *l) == Some(true))
+        {
+            return true;
+        }
+        clause.retain(|l| lit_value(&self.values, *l).is_none());
+
+        match clause.len() {
+            0 => self.inconsistent = true,
+            1 => {
+                self.enqueue(clause[0], None);
+                if self.propagate().is_some() {
+                    self.inconsistent = true;
+                }
+            }
+            _ => {
+                self.clauses.push(clause);
+                self.attach(self.clauses.len() - 1);
+            }
+        }
+        !self.inconsistent
+    }
+
+    fn attach(&mut self, clause: usize) {
+        let (first, second) = (self.clauses[clause][0], self.clauses[clause][1]);
+        self.watches[first.index()].push(clause);
+        self.watches[second.index()].push(clause);
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+        let var = lit.var();
+        self.values[var] = Some(lit.is_positive());
+        self.level[var] = self.decision_level();
+        self.reason[var] = reason;
+        self.trail.push(lit);
+    }
+
+    fn propagate(&mut self) -> Option<usize> {
+        while self.qhead < self.trail.len() {
+            let false_lit = !self.trail[self.qhead];
+            self.qhead += 1;
+
+            let mut watchers = std::mem::take(&mut self.watches[false_lit.index()]);
+            let mut conflict = None;
+            let mut kept = 0;
+            let mut i = 0;
+
+            while i < watchers.len() {
+                let ci = watchers[i];
+                i += 1;
+                let clause = &mut self.clauses[ci];
+                if clause[0] == false_lit {
+                    clause.swap(0, 1);
+                }
+
+                let first = clause[0];
+                if lit_value(&self.values, first) == Some(true) {
+                    watchers[kept] = ci;
+                    kept += 1;
+                    continue;
+                }
+
+                let replacement =
+                    (2..clause.len()).find(|k| lit_value(&self.values, clause[*k]) != Some(false));
+                if let Some(k) = replacement {
+                    clause.swap(1, k);
+                    self.watches[clause[1].index()].push(ci);
+                    continue;
+                }
+
+                watchers[kept] = ci;
+                kept += 1;
+                if lit_value(&self.values, first) == Some(false) {
+                    conflict = Some(ci);
+                    while i < watchers.len() {
+                        watchers[kept] = watchers[i];
+                        kept += 1;
+                        i += 1;
+                    }
+                } else {
+                    self.enqueue(first, Some(ci));
+                }
+            }
+
+            watchers.truncate(kept);
+            self.watches[false_lit.index()] = watchers;
+            if conflict.is_some() {
+                self.qhead = self.trail.len();
+                return conflict;
+            }
+        }
+        None
+    }
+
+    fn bump(&mut self, var: usize) {
+        self.activity[var] += self.var_inc;
+        if self.activity[var] > 1e100 {
+            for a in self.activity.iter_mut() {
+                *a *= 1e-100;
+            }
+            self.var_inc *= 1e-100;
+        }
+        self.order.increased(var, &self.activity);
+    }
+
+    fn analyze(&mut self, conflict: usize) -> (Vec<Lit>, usize) {
+        let mut seen = vec![false; self.num_vars()];
+        let mut learnt = vec![Lit(0)];
+        let mut counter = 0;
+        let mut clause = conflict;
+        let mut index = self.trail.len();
+        let mut skip = 0;
+
+        let asserting = loop {
+            for k in skip..self.clauses[clause].len() {
+                let q = self.clauses[clause][k];
+                let var = q.var();
+                if seen[var] || self.level[var] == 0 {
+                    continue;
+                }
+                seen[var] = true;
+                self.bump(var);
+                if self.level[var] >= self.decision_level() {
+                    counter += 1;
+                } else {
+                    learnt.push(q);
+                }
+            }
+
+            loop {
+                index -= 1;
+                if seen[self.trail[index].var()] {
+                    break;
+                }
+            }
+            let p = self.trail[index];
+            seen[p.var()] = false;
+            counter -= 1;
+            if counter == 0 {
+                break p;
+            }
+            clause = self.reason[p.var()].unwrap();
+            skip = 1;
+        };
+        learnt[0] = !asserting;
+
+        let mut backjump = 0;
+        if learnt.len() > 1 {
+            let max = (1..learnt.len())
+                .max_by_key(|k| self.level[learnt[*k].var()])
+                .unwrap();
+            learnt.swap(1, max);
+            backjump = self.level[learnt[1].var()];
+        }
+        (learnt, backjump)
+    }
+
+    fn cancel_until(&mut self, level: usize) {
+        if self.decision_level() <= level {
+            return;
+        }
+        for lit in self.trail.drain(self.trail_lim[level]..) {
+            let var = lit.var();
+            self.values[var] = None;
+            self.reason[var] = None;
+            self.phase[var] = lit.is_positive();
+            self.order.push(var, &self.activity);
+        }
+        self.trail_lim.truncate(level);
+        self.qhead = self.trail.len();
+    }
+
+    fn pick_branch(&mut self) -> Option<Lit> {
+        while let Some(var) = self.order.pop(&self.activity) {
+            if self.values[var].is_none() {
+                return Some(Lit::new(var, self.phase[var]));
+            }
+        }
+        None
+    }
+
+    pub fn solve(&mut self) -> Option<Vec<bool>> {
+        if self.inconsistent {
+            return None;
+        }
+        self.cancel_until(0);
+        if self.propagate().is_some() {
+            self.inconsistent = true;
+            return None;
+        }
+
+        let mut restarts = 1;
+        let mut conflicts = 0;
+        loop {
+            if let Some(conflict) = self.propagate() {
+                conflicts += 1;
+                if self.decision_level() == 0 {
+                    self.inconsistent = true;
+                    return None;
+                }
+
+                let (learnt, backjump) = self.analyze(conflict);
+                self.cancel_until(backjump);
+                if learnt.len() == 1 {
+                    self.enqueue(learnt[0], None);
+                } else {
+                    self.clauses.push(learnt);
+                    let ci = self.clauses.len() - 1;
+                    self.attach(ci);
+                    self.enqueue(self.clauses[ci][0], Some(ci));
+                }
+                self.var_inc /= 0.95;
+                continue;
+            }
+
+            if conflicts >= luby(restarts) * 100 {
+                conflicts = 0;
+                restarts += 1;
+                self.cancel_until(0);
+                continue;
+            }
+
+            match self.pick_branch() {
+                Some(lit) => {
+                    self.trail_lim.push(self.trail.len());
+                    self.enqueue(lit, None);
+                }
+                None => {
+                    let model = self.values.iter().map(|v| v.unwrap()).collect();
+                    self.cancel_until(0);
+                    return Some(model);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,671 @@
+use crate::adder::adder;
+use crate::boolean_evaluation::{eval_formula, eval_formula_with, ParseError};
+use crate::conjunctive_normal_form::conjunctive_normal_form_with;
+use crate::curve::map;
+use crate::dimacs::{dimacs_to_formula, formula_to_dimacs};
+use crate::disjunctive_normal_form::{canonical_cnf, canonical_dnf, disjunctive_normal_form};
+use crate::gray_code::gray_code;
+use crate::infix::{infix_to_rpn, rpn_to_infix, rpn_to_unicode};
+use crate::inverse_function::reverse_map;
+use crate::minimize::{minimize, Form};
+use crate::multiplier::multiplier;
+use crate::negation_normal_form::negation_normal_form;
+use crate::powerset::powerset;
+use crate::repl::run_repl;
+use crate::sat::{count_models, models, sat, sat_assignment};
+use crate::set_evaluation::{
+    eval_named_set, eval_named_set_in, eval_set, eval_set_in, SetError, Universe,
+};
+use crate::simplify::simplify;
+use crate::symbol_table::{build_named_ast, SymbolTable};
+use crate::truth_table::{TableFormat, TruthTable};
+use crate::tseitin::CnfMode;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{BufRead, Write};
+
+const USAGE: &str = "usage: ready-set-boole <command> [options] [formula...]
+
+commands:
+  eval       evaluate formulas (use --assign name=0|1 for variables)
+  table      print the truth table of formulas
+  nnf        convert formulas to negation normal form
+  min        minimize formulas to a sum of products (--cnf for a product of sums)
+  cnf        convert formulas to conjunctive normal form
+  dnf        convert formulas to disjunctive normal form
+  simplify   remove redundant literals and clauses from formulas
+  sat        check satisfiability (--model, --all, --count)
+  sets       evaluate a set formula (- is difference): sets <formula> <1,2,3>... or --set name=1,2,3
+  repl       start an interactive shell
+  dimacs     read a DIMACS CNF problem and print it as a formula
+  powerset   print the powerset of the given integers
+  gray       print the Gray code of the given integers
+  curve      map <x> <y> onto the Z-order curve, or --inverse <n>
+  add        add two integers with the bitwise adder
+  mul        multiply two integers with the bitwise multiplier
+
+options:
+  -f, --file <path>     read formulas from a file, one per line
+  --format <notation>   rpn (default), infix or unicode
+  --table-format <fmt>  markdown (default), csv, json, latex or hex
+  --mode <mode>         cnf encoding: equivalent (default), tseitin or pg
+  --dimacs              print cnf output as DIMACS
+  --cnf                 minimize to a product of sums
+  --simplify            simplify nnf, cnf and dnf output
+  --canonical           print the canonical minterm (dnf) or maxterm (cnf) form
+  --dont-care <formula> treat assignments satisfying this formula as don't-cares
+  --universe <1,2,3>    domain for set complements (default: union of the sets)
+  --json                print one JSON object per input
+  -h, --help            print this message
+
+Formulas are read from the arguments, from --file, or from stdin when neither is given.";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Rpn,
+    Infix,
+    Unicode,
+}
+
+#[derive(Debug, Default)]
+pub struct Options {
+    pub command: String,
+    pub format: Format,
+    pub table_format: TableFormat,
+    pub json: bool,
+    pub args: Vec<String>,
+    pub files: Vec<String>,
+    pub assignments: Vec<String>,
+    pub sets: Vec<String>,
+    pub universe: Option<String>,
+    pub mode: Option<CnfMode>,
+    pub dimacs: bool,
+    pub cnf: bool,
+    pub canonical: bool,
+    pub simplify: bool,
+    pub dont_care: Option<String>,
+    pub model: bool,
+    pub all: bool,
+    pub count: bool,
+    pub inverse: bool,
+    pub help: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CliError {
+    Usage(String),
+    Io(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Usage(msg) => write!(f, "{}", msg),
+            CliError::Io(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+enum FormulaError {
+    Parse(ParseError),
+    Set(SetError),
+}
+
+impl FormulaError {
+    fn offset(&self) -> Option<usize> {
+        match self {
+            FormulaError::Parse(e) => e.offset(),
+            FormulaError::Set(e) => e.offset(),
+        }
+    }
+}
+
+impl From<ParseError> for FormulaError {
+    fn from(e: ParseError) -> Self {
+        FormulaError::Parse(e)
+    }
+}
+
+impl fmt::Display for FormulaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormulaError::Parse(e) => write!(f, "{}", e),
+            FormulaError::Set(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+enum Output {
+    Bool(bool),
+    Formula(String),
+    Text(String),
+    Model(Option<BTreeMap<String, bool>>),
+    Models(Vec<BTreeMap<String, bool>>),
+    Count(Option<u128>),
+    Table { text: String, table: TruthTable },
+    Set(Vec<i32>),
+}
+
+pub fn parse_args(args: &[String]) -> Result<Options, CliError> {
+    let mut opts = Options::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| CliError::Usage(format!("missing value for {}", name)))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => opts.help = true,
+            "--json" => opts.json = true,
+            "--dimacs" => opts.dimacs = true,
+            "--cnf" => opts.cnf = true,
+            "--canonical" => opts.canonical = true,
+            "--simplify" => opts.simplify = true,
+            "--dont-care" => opts.dont_care = Some(value(arg)?),
+            "--model" => opts.model = true,
+            "--all" => opts.all = true,
+            "--count" => opts.count = true,
+            "--inverse" => opts.inverse = true,
+            "-f" | "--file" => opts.files.push(value(arg)?),
+            "--assign" => opts.assignments.push(value(arg)?),
+            "--set" => opts.sets.push(value(arg)?),
+            "--universe" => opts.universe = Some(value(arg)?),
+            "--format" => {
+                opts.format = match value(arg)?.as_str() {
+                    "rpn" => Format::Rpn,
+                    "infix" => Format::Infix,
+                    "unicode" => Format::Unicode,
+                    other => return Err(CliError::Usage(format!("unknown format '{}'", other))),
+                }
+            }
+            "--table-format" => {
+                opts.table_format = match value(arg)?.as_str() {
+                    "markdown" => TableFormat::Markdown,
+                    "csv" => TableFormat::Csv,
+                    "json" => TableFormat::Json,
+                    "latex" => TableFormat::Latex,
+                    "hex" => TableFormat::Hex,
+                    other => {
+                        return Err(CliError::Usage(format!("unknown table format '{}'", other)))
+                    }
+                }
+            }
+            "--mode" => {
+                opts.mode = Some(match value(arg)?.as_str() {
+                    "equivalent" => CnfMode::Equivalent,
+                    "tseitin" => CnfMode::Tseitin,
+                    "pg" => CnfMode::PlaistedGreenbaum,
+                    other => return Err(CliError::Usage(format!("unknown cnf mode '{}'", other))),
+                })
+            }
+            _ if arg.starts_with("--") => {
+                return Err(CliError::Usage(format!("unknown option '{}'", arg)))
+            }
+            _ if opts.command.is_empty() => opts.command = arg.clone(),
+            _ => opts.args.push(arg.clone()),
+        }
+    }
+
+    if opts.command.is_empty() && !opts.help {
+        return Err(CliError::Usage("missing command".to_string()));
+    }
+    Ok(opts)
+}
+
+fn read_inputs(
+    opts: &Options,
+    positional: &[String],
+    stdin: &mut dyn BufRead,
+) -> Result<Vec<String>, CliError> {
+    let mut inputs: Vec<String> = positional.to_vec();
+    for file in opts.files.iter() {
+        let content =
+            fs::read_to_string(file).map_err(|e| CliError::Io(format!("{}: {}", file, e)))?;
+        inputs.extend(content.lines().map(|l| l.to_string()));
+    }
+    if positional.is_empty() && opts.files.is_empty() {
+        for line in stdin.lines() {
+            inputs.push(line.map_err(|e| CliError::Io(e.to_string()))?);
+        }
+    }
+
+    Ok(inputs
+        .into_iter()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .collect())
+}
+
+fn to_rpn(formula: &str, format: Format) -> Result<String, ParseError> {
+    match format {
+        Format::Rpn => Ok(formula.to_string()),
+        Format::Infix | Format::Unicode => infix_to_rpn(formula),
+    }
+}
+
+pub fn from_rpn(formula: &str, format: Format) -> String {
+    match format {
+        Format::Rpn => formula.to_string(),
+        Format::Infix => rpn_to_infix(formula).unwrap(),
+        Format::Unicode => rpn_to_unicode(formula).unwrap(),
+    }
+}
+
+fn parse_assignments(opts: &Options) -> Result<BTreeMap<String, bool>, CliError> {
+    let mut assignment = BTreeMap::new();
+    for pair in opts.assignments.iter().flat_map(|a| a.split(',')) {
+        match pair.split_once('=') {
+            Some((name, "0" | "false")) => assignment.insert(name.trim().to_string(), false),
+            Some((name, "1" | "true")) => assignment.insert(name.trim().to_string(), true),
+            _ => return Err(CliError::Usage(format!("invalid assignment '{}'", pair))),
+        };
+    }
+    Ok(assignment)
+}
+
+fn parse_set(set: &str) -> Result<Vec<i32>, CliError> {
+    set.split(',')
+        .map(|n| n.trim())
+        .filter(|n| !n.is_empty())
+        .map(|n| {
+            n.parse()
+                .map_err(|_| CliError::Usage(format!("invalid set element '{}'", n)))
+        })
+        .collect()
+}
+
+fn parse_numbers<T: std::str::FromStr>(args: &[String]) -> Result<Vec<T>, CliError> {
+    args.iter()
+        .map(|a| {
+            a.parse()
+                .map_err(|_| CliError::Usage(format!("invalid number '{}'", a)))
+        })
+        .collect()
+}
+
+fn run_formula(
+    opts: &Options,
+    formula: &str,
+    extra: &Extra,
+) -> Result<Output, (String, FormulaError)> {
+    let rpn = to_rpn(formula, opts.format).map_err(|e| (formula.to_string(), e.into()))?;
+    let shown = if opts.format == Format::Rpn {
+        formula.to_string()
+    } else {
+        rpn.clone()
+    };
+    let fail = |e: ParseError| (shown.clone(), FormulaError::Parse(e));
+    let simplified = |res: Result<String, ParseError>| match res {
+        Ok(formula) if opts.simplify => simplify(&formula).map_err(fail),
+        res => res.map_err(fail),
+    };
+
+    Ok(match opts.command.as_str() {
+        "eval" if extra.assignment.is_empty() => Output::Bool(eval_formula(&rpn).map_err(fail)?),
+        "eval" => Output::Bool(eval_formula_with(&rpn, &extra.assignment).map_err(fail)?),
+        "table" => {
+            let table = TruthTable::from_formula(&rpn).map_err(fail)?;
+            Output::Table {
+                text: table.render(opts.table_format),
+                table,
+            }
+        }
+        "min" => {
+            let dont_care = match opts.dont_care.as_deref() {
+                Some(dc) => {
+                    let rpn = to_rpn(dc, opts.format).map_err(|e| (dc.to_string(), e.into()))?;
+                    build_named_ast(&rpn, &mut SymbolTable::new())
+                        .map_err(|e| (rpn.clone(), e.into()))?;
+                    Some(rpn)
+                }
+                None => None,
+            };
+            let form = if opts.cnf { Form::Cnf } else { Form::Dnf };
+            Output::Formula(from_rpn(
+                &minimize(&rpn, dont_care.as_deref(), form).map_err(fail)?,
+                opts.format,
+            ))
+        }
+        "nnf" => Output::Formula(from_rpn(
+            &simplified(negation_normal_form(&rpn))?,
+            opts.format,
+        )),
+        "cnf" if opts.canonical => {
+            Output::Formula(from_rpn(&canonical_cnf(&rpn).map_err(fail)?, opts.format))
+        }
+        "dnf" if opts.canonical => {
+            Output::Formula(from_rpn(&canonical_dnf(&rpn).map_err(fail)?, opts.format))
+        }
+        "dnf" => Output::Formula(from_rpn(
+            &simplified(disjunctive_normal_form(&rpn))?,
+            opts.format,
+        )),
+        "cnf" if opts.dimacs => Output::Text(
+            formula_to_dimacs(&rpn, opts.mode.unwrap_or(CnfMode::Equivalent)).map_err(fail)?,
+        ),
+        "cnf" => Output::Formula(from_rpn(
+            &simplified(conjunctive_normal_form_with(
+                &rpn,
+                opts.mode.unwrap_or(CnfMode::Equivalent),
+            ))?,
+            opts.format,
+        )),
+        "simplify" => Output::Formula(from_rpn(&simplify(&rpn).map_err(fail)?, opts.format)),
+        "sat" if opts.count => Output::Count(count_models(&rpn).map_err(fail)?),
+        "sat" if opts.all => Output::Models(models(&rpn).map_err(fail)?.collect()),
+        "sat" if opts.model => Output::Model(sat_assignment(&rpn).map_err(fail)?),
+        "sat" => Output::Bool(sat(&rpn).map_err(fail)?),
+        _ => Output::Set(
+            match (&extra.universe, extra.named_sets.is_empty()) {
+                (Some(universe), false) => eval_named_set_in(&rpn, &extra.named_sets, universe),
+                (Some(universe), true) => eval_set_in(&rpn, &extra.sets, universe),
+                (None, false) => eval_named_set(&rpn, &extra.named_sets),
+                (None, true) => eval_set(&rpn, extra.sets.clone()),
+            }
+            .map_err(|e| (shown.clone(), FormulaError::Set(e)))?,
+        ),
+    })
+}
+
+#[derive(Default)]
+struct Extra {
+    assignment: BTreeMap<String, bool>,
+    sets: Vec<Vec<i32>>,
+    named_sets: BTreeMap<String, Vec<i32>>,
+    universe: Option<Universe<i32>>,
+}
+
+fn json_string(s: &str) -> String {
+    let mut res = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+fn json_model(model: &BTreeMap<String, bool>) -> String {
+    let fields: Vec<String> = model
+        .iter()
+        .map(|(k, v)| format!("{}: {}", json_string(k), v))
+        .collect();
+    format!("{{{}}}", fields.join(", "))
+}
+
+fn json_list<T: fmt::Display>(items: &[T]) -> String {
+    let items: Vec<String> = items.iter().map(|i| i.to_string()).collect();
+    format!("[{}]", items.join(", "))
+}
+
+fn plain_model(model: &BTreeMap<String, bool>) -> String {
+    let fields: Vec<String> = model
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, *v as u8))
+        .collect();
+    fields.join(" ")
+}
+
+fn render(output: &Output, formula: &str, json: bool) -> String {
+    if !json {
+        return match output {
+            Output::Bool(b) => b.to_string(),
+            Output::Formula(f) => f.clone(),
+            Output::Text(t) | Output::Table { text: t, .. } => t.trim_end().to_string(),
+            Output::Model(Some(m)) => plain_model(m),
+            Output::Model(None) => "unsatisfiable".to_string(),
+            Output::Models(ms) => {
+                let lines: Vec<String> = ms.iter().map(plain_model).collect();
+                lines.join("\n")
+            }
+            Output::Count(Some(n)) => n.to_string(),
+            Output::Count(None) => "more than 2^128".to_string(),
+            Output::Set(s) => format!("{:?}", s),
+        };
+    }
+
+    let result = match output {
+        Output::Bool(b) => format!("\"result\": {}", b),
+        Output::Formula(f) | Output::Text(f) => format!("\"result\": {}", json_string(f)),
+        Output::Model(m) => format!(
+            "\"satisfiable\": {}, \"model\": {}",
+            m.is_some(),
+            m.as_ref().map_or("null".to_string(), json_model)
+        ),
+        Output::Models(ms) => {
+            let models: Vec<String> = ms.iter().map(json_model).collect();
+            format!("\"models\": [{}]", models.join(", "))
+        }
+        Output::Count(n) => format!(
+            "\"count\": {}",
+            n.map_or("null".to_string(), |n| n.to_string())
+        ),
+        Output::Table { table, .. } => {
+            let names: Vec<String> = table.variables().iter().map(|v| json_string(v)).collect();
+            let rows: Vec<String> = table
+                .outputs()
+                .enumerate()
+                .map(|(i, result)| {
+                    format!(
+                        "{{\"values\": {}, \"result\": {}}}",
+                        json_list(&table.row(i)),
+                        result
+                    )
+                })
+                .collect();
+            format!(
+                "\"variables\": [{}], \"rows\": [{}]",
+                names.join(", "),
+                rows.join(", ")
+            )
+        }
+        Output::Set(s) => format!("\"result\": {}", json_list(s)),
+    };
+    format!("{{\"formula\": {}, {}}}", json_string(formula), result)
+}
+
+pub fn render_error(
+    formula: &str,
+    shown: &str,
+    err: &dyn fmt::Display,
+    offset: Option<usize>,
+    json: bool,
+) -> String {
+    if json {
+        return format!(
+            "{{\"formula\": {}, \"error\": {}, \"offset\": {}}}",
+            json_string(formula),
+            json_string(&err.to_string()),
+            offset.map_or("null".to_string(), |o| o.to_string())
+        );
+    }
+    match offset {
+        Some(offset) => format!("error: {}\n    {}\n    {}^", err, shown, " ".repeat(offset)),
+        None => format!("error: {}", err),
+    }
+}
+
+fn run_numbers(opts: &Options) -> Result<String, CliError> {
+    let wrong_arity = || CliError::Usage(format!("wrong number of arguments for {}", opts.command));
+    let res = match opts.command.as_str() {
+        "powerset" => format!("{:?}", powerset(parse_numbers(&opts.args)?)),
+        "gray" => {
+            let codes: Vec<String> = parse_numbers::<u32>(&opts.args)?
+                .into_iter()
+                .map(|n| gray_code(n).to_string())
+                .collect();
+            codes.join("\n")
+        }
+        "curve" if opts.inverse => match parse_numbers::<f64>(&opts.args)?.as_slice() {
+            [n] if (0.0..=1.0).contains(n) => {
+                let (x, y) = reverse_map(*n);
+                format!("{} {}", x, y)
+            }
+            [_] => return Err(CliError::Usage("value must be in [0, 1]".to_string())),
+            _ => return Err(wrong_arity()),
+        },
+        "curve" => match parse_numbers::<u16>(&opts.args)?.as_slice() {
+            [x, y] => map(*x, *y).to_string(),
+            _ => return Err(wrong_arity()),
+        },
+        "add" | "mul" => match parse_numbers::<u32>(&opts.args)?.as_slice() {
+            [a, b] if opts.command == "add" => adder(*a, *b).to_string(),
+            [a, b] => multiplier(*a, *b).to_string(),
+            _ => return Err(wrong_arity()),
+        },
+        _ => {
+            return Err(CliError::Usage(format!(
+                "unknown command '{}'",
+                opts.command
+            )))
+        }
+    };
+    if !opts.json {
+        return Ok(res);
+    }
+    let values: Vec<&str> = res.split(['\n', ' ']).collect();
+    Ok(format!(
+        "{{\"command\": {}, \"result\": {}}}",
+        json_string(&opts.command),
+        if values.len() == 1 && opts.command != "gray" {
+            values[0].to_string()
+        } else if opts.command == "powerset" {
+            res.clone()
+        } else {
+            json_list(&values)
+        }
+    ))
+}
+
+fn run_command(
+    opts: &Options,
+    stdin: &mut dyn BufRead,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Result<bool, CliError> {
+    let io = |e: std::io::Error| CliError::Io(e.to_string());
+
+    if opts.command == "repl" {
+        run_repl(opts.format).map_err(|e| CliError::Io(e.to_string()))?;
+        return Ok(true);
+    }
+
+    if opts.command == "dimacs" {
+        let mut input = String::new();
+        for file in opts.files.iter().chain(opts.args.iter()) {
+            input.push_str(
+                &fs::read_to_string(file).map_err(|e| CliError::Io(format!("{}: {}", file, e)))?,
+            );
+        }
+        if opts.files.is_empty() && opts.args.is_empty() {
+            stdin.read_to_string(&mut input).map_err(io)?;
+        }
+        let formula = match dimacs_to_formula(&input) {
+            Ok(formula) => from_rpn(&formula, opts.format),
+            Err(e) if opts.json => {
+                writeln!(out, "{{\"error\": {}}}", json_string(&e.to_string())).map_err(io)?;
+                return Ok(false);
+            }
+            Err(e) => {
+                writeln!(err, "error: {}", e).map_err(io)?;
+                return Ok(false);
+            }
+        };
+        let output = if opts.json {
+            format!("{{\"result\": {}}}", json_string(&formula))
+        } else {
+            formula
+        };
+        writeln!(out, "{}", output).map_err(io)?;
+        return Ok(true);
+    }
+
+    if ![
+        "eval", "table", "nnf", "min", "cnf", "dnf", "simplify", "sat", "sets",
+    ]
+    .contains(&opts.command.as_str())
+    {
+        writeln!(out, "{}", run_numbers(opts)?).map_err(io)?;
+        return Ok(true);
+    }
+
+    let mut extra = Extra {
+        assignment: parse_assignments(opts)?,
+        ..Default::default()
+    };
+    let positional = if opts.command == "sets" {
+        let (formula, sets) = opts.args.split_at(opts.args.len().min(1));
+        extra.sets = sets
+            .iter()
+            .map(|s| parse_set(s))
+            .collect::<Result<_, _>>()?;
+        for set in opts.sets.iter() {
+            let Some((name, elements)) = set.split_once('=') else {
+                return Err(CliError::Usage(format!("invalid named set '{}'", set)));
+            };
+            extra
+                .named_sets
+                .insert(name.trim().to_string(), parse_set(elements)?);
+        }
+        if let Some(universe) = opts.universe.as_deref() {
+            extra.universe = Some(Universe::new(parse_set(universe)?));
+        }
+        formula.to_vec()
+    } else {
+        opts.args.clone()
+    };
+
+    let mut ok = true;
+    for formula in read_inputs(opts, &positional, stdin)? {
+        match run_formula(opts, &formula, &extra) {
+            Ok(output) => writeln!(out, "{}", render(&output, &formula, opts.json)).map_err(io)?,
+            Err((shown, e)) => {
+                ok = false;
+                let msg = render_error(&formula, &shown, &e, e.offset(), opts.json);
+                if opts.json {
+                    writeln!(out, "{}", msg).map_err(io)?;
+                } else {
+                    writeln!(err, "{}", msg).map_err(io)?;
+                }
+            }
+        }
+    }
+    Ok(ok)
+}
+
+pub fn run(
+    args: &[String],
+    stdin: &mut dyn BufRead,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> i32 {
+    let result = parse_args(args).and_then(|opts| {
+        if opts.help {
+            writeln!(out, "{}", USAGE).map_err(|e| CliError::Io(e.to_string()))?;
+            return Ok(true);
+        }
+        run_command(&opts, stdin, out, err)
+    });
+
+    match result {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(CliError::Usage(msg)) => {
+            let _ = writeln!(err, "error: {}\n\n{}", msg, USAGE);
+            2
+        }
+        Err(CliError::Io(msg)) => {
+            let _ = writeln!(err, "error: {}", msg);
+            1
+        }
+    }
+}
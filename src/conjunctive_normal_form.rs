@@ -1,110 +1,123 @@
-use crate::boolean_evaluation::build_ast;
+use crate::boolean_evaluation::fold_constants;
 use crate::boolean_evaluation::ASTNode;
-use crate::negation_normal_form::tree_to_almost_nnf;
+use crate::boolean_evaluation::Atom;
+use crate::boolean_evaluation::ParseError;
+use crate::cdcl::Lit;
+use crate::dag::Dag;
 use crate::negation_normal_form::tree_to_string;
-use std::mem;
+use crate::symbol_table::{build_named_ast, Symbol, SymbolTable};
+use crate::tseitin::{tseitin_clauses, CnfMode};
+use std::hash::Hash;
 
-pub fn tree_to_almost_cnf(node: &mut ASTNode<char>, modified: &mut bool) {
-    match node {
-        ASTNode::Op {
-            operator,
-            left,
-            right,
-        } => {
-            if *operator == '|' {
-                if let ASTNode::Op {
-                    operator: '&',
-                    left: right_left,
-                    right: right_right,
-                } = &mut **right
-                {
-                    *node = ASTNode::Op {
-                        operator: '&',
-                        left: Some(Box::new(ASTNode::Op {
-                            operator: '|',
-                            left: left.clone(),
-                            right: right_left.take().unwrap(),
-                        })),
-                        right: Box::new(ASTNode::Op {
-                            operator: '|',
-                            left: left.take(),
-                            right: Box::new(mem::replace(right_right, ASTNode::Value('\0'))),
-                        }),
-                    };
-
-                    *modified = true;
-                    let ASTNode::Op { left, right, .. } = node else {
-                        return;
-                    };
-                    if let Some(l) = left.as_mut() {
-                        tree_to_almost_cnf(l, modified);
-                    }
-                    tree_to_almost_cnf(right, modified);
-                    return;
-                }
+pub fn cnf_tree<V: Atom + Eq + Hash>(tree: ASTNode<V>) -> ASTNode<V> {
+    let mut dag = Dag::new();
+    let root = dag.from_ast(&fold_constants(tree));
+    let root = dag.cnf(root);
+    dag.to_ast(root)
+}
 
-                if let Some(left_node) = left.as_mut() {
-                    if let ASTNode::Op {
-                        operator: '&',
-                        left: left_left,
-                        right: left_right,
-                    } = &mut **left_node
-                    {
-                        *node = ASTNode::Op {
-                            operator: '&',
-                            left: Some(Box::new(ASTNode::Op {
-                                operator: '|',
-                                left: Some(right.clone()),
-                                right: left_left.take().unwrap(),
-                            })),
-                            right: Box::new(ASTNode::Op {
-                                operator: '|',
-                                left: Some(Box::new(mem::replace(right, ASTNode::Value('\0')))),
-                                right: Box::new(mem::replace(left_right, ASTNode::Value('\0'))),
-                            }),
-                        };
-                        *modified = true;
-                    }
+fn clause_lits(node: &ASTNode<Symbol>) -> Vec<Lit> {
+    let mut clause = Vec::new();
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        match node {
+            ASTNode::Value(Symbol::Var(id)) => clause.push(Lit::new(*id, true)),
+            ASTNode::Op {
+                operator: '!',
+                right,
+                ..
+            } => {
+                if let ASTNode::Value(Symbol::Var(id)) = **right {
+                    clause.push(Lit::new(id, false));
                 }
             }
-
-            let ASTNode::Op { left, right, .. } = node else {
-                return;
-            };
-            if let Some(l) = left.as_mut() {
-                tree_to_almost_cnf(l, modified);
+            ASTNode::Op {
+                operator: '|',
+                left,
+                right,
+            } => {
+                stack.push(right);
+                stack.push(left.as_ref().unwrap());
             }
-            tree_to_almost_cnf(right, modified);
+            _ => {}
         }
-        _ => {}
     }
+    clause
 }
 
-#[allow(non_snake_case)]
-pub fn conjunctive_normal_form(formula: &str) -> String {
-    if formula.contains('1') || formula.contains('0') {
-        println!("Formula contains constants (0 or 1), cannot convert to CNF");
-        return String::new();
-    }
-    let mut tree = match build_ast(formula) {
-        Some(ast) => ast,
-        None => {
-            println!("Error in formula");
-            return String::new();
+pub fn cnf_clauses(node: &ASTNode<Symbol>, clauses: &mut Vec<Vec<Lit>>) {
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        match node {
+            ASTNode::Value(Symbol::True) => {}
+            ASTNode::Op {
+                operator: '&',
+                left,
+                right,
+            } => {
+                stack.push(right);
+                stack.push(left.as_ref().unwrap());
+            }
+            _ => clauses.push(clause_lits(node)),
         }
-    };
+    }
+}
 
-    let mut modified = true;
-    while modified {
-        modified = false;
-        tree_to_almost_nnf(&mut tree, &mut modified);
+pub fn fold_right<V: Default>(mut nodes: Vec<ASTNode<V>>, operator: char, empty: V) -> ASTNode<V> {
+    let Some(mut tree) = nodes.pop() else {
+        return ASTNode::Value(empty);
+    };
+    while let Some(node) = nodes.pop() {
+        tree = ASTNode::Op {
+            operator,
+            left: Some(Box::new(tree)),
+            right: Box::new(node),
+        };
     }
+    tree
+}
+
+pub fn clauses_to_tree(clauses: &[Vec<Lit>]) -> ASTNode<Symbol> {
+    let clauses = clauses
+        .iter()
+        .map(|clause| {
+            let lits = clause
+                .iter()
+                .map(|lit| {
+                    let var = ASTNode::Value(Symbol::Var(lit.var()));
+                    if lit.is_positive() {
+                        var
+                    } else {
+                        ASTNode::Op {
+                            operator: '!',
+                            left: None,
+                            right: Box::new(var),
+                        }
+                    }
+                })
+                .collect();
+            fold_right(lits, '|', Symbol::False)
+        })
+        .collect();
+    fold_right(clauses, '&', Symbol::True)
+}
+
+#[allow(non_snake_case)]
+pub fn conjunctive_normal_form(formula: &str) -> Result<String, ParseError> {
+    let mut table = SymbolTable::new();
+    let tree = build_named_ast(formula, &mut table)?;
 
-    modified = true;
-    while modified {
-        modified = false;
-        tree_to_almost_cnf(&mut tree, &mut modified);
+    Ok(tree_to_string(&table.resolve(&cnf_tree(tree))))
+}
+
+pub fn conjunctive_normal_form_with(formula: &str, mode: CnfMode) -> Result<String, ParseError> {
+    if mode == CnfMode::Equivalent {
+        return conjunctive_normal_form(formula);
     }
 
-    tree_to_string(&tree)
+    let mut table = SymbolTable::new();
+    let tree = fold_constants(build_named_ast(formula, &mut table)?);
+
+    let clauses = tseitin_clauses(&tree, &mut table, mode == CnfMode::PlaistedGreenbaum);
+    Ok(tree_to_string(&table.resolve(&clauses_to_tree(&clauses))))
 }
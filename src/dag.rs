@@ -0,0 +1,447 @@
+use crate::boolean_evaluation::{ASTNode, Atom};
+use crate::cdcl::Lit;
+use crate::symbol_table::Symbol;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DagNode<V> {
+    Value(V),
+    Op {
+        operator: char,
+        left: Option<NodeId>,
+        right: NodeId,
+    },
+}
+
+pub struct Dag<V> {
+    nodes: Vec<DagNode<V>>,
+    unique: HashMap<DagNode<V>, NodeId>,
+    polarity: Vec<[Option<NodeId>; 2]>,
+    normal: Vec<[Option<NodeId>; 2]>,
+    distributed: HashMap<(char, NodeId, NodeId), NodeId>,
+}
+
+impl<V> Default for Dag<V> {
+    fn default() -> Self {
+        Dag {
+            nodes: Vec::new(),
+            unique: HashMap::new(),
+            polarity: Vec::new(),
+            normal: Vec::new(),
+            distributed: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Task {
+    Polarity(NodeId, bool),
+    Normal(char, NodeId),
+    Distribute(char, NodeId, NodeId),
+}
+
+enum Step<T> {
+    Visit(T),
+    Combine(char, bool),
+}
+
+fn slot(memo: &mut Vec<[Option<NodeId>; 2]>, id: NodeId) -> &mut [Option<NodeId>; 2] {
+    if memo.len() <= id.0 {
+        memo.resize(id.0 + 1, [None; 2]);
+    }
+    &mut memo[id.0]
+}
+
+fn dual(operator: char) -> char {
+    if operator == '&' {
+        '|'
+    } else {
+        '&'
+    }
+}
+
+impl<V: Atom + Eq + Hash> Dag<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn nodes(&self) -> &[DagNode<V>] {
+        &self.nodes
+    }
+
+    pub fn node(&self, id: NodeId) -> &DagNode<V> {
+        &self.nodes[id.0]
+    }
+
+    fn intern(&mut self, node: DagNode<V>) -> NodeId {
+        let nodes = &mut self.nodes;
+        *self.unique.entry(node).or_insert_with_key(|node| {
+            nodes.push(node.clone());
+            NodeId(nodes.len() - 1)
+        })
+    }
+
+    pub fn value(&mut self, value: V) -> NodeId {
+        self.intern(DagNode::Value(value))
+    }
+
+    pub fn op(&mut self, operator: char, left: NodeId, right: NodeId) -> NodeId {
+        self.intern(DagNode::Op {
+            operator,
+            left: Some(left),
+            right,
+        })
+    }
+
+    pub fn not(&mut self, id: NodeId) -> NodeId {
+        self.intern(DagNode::Op {
+            operator: '!',
+            left: None,
+            right: id,
+        })
+    }
+
+    pub fn from_ast(&mut self, node: &ASTNode<V>) -> NodeId {
+        let mut stack = vec![Step::Visit(node)];
+        let mut ids = Vec::new();
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Visit(ASTNode::Value(v)) => ids.push(self.value(v.clone())),
+                Step::Visit(ASTNode::Op {
+                    operator,
+                    left,
+                    right,
+                }) => {
+                    stack.push(Step::Combine(*operator, left.is_some()));
+                    stack.push(Step::Visit(right));
+                    if let Some(l) = left.as_ref() {
+                        stack.push(Step::Visit(l));
+                    }
+                }
+                Step::Combine(operator, binary) => {
+                    let right = ids.pop().unwrap();
+                    let left = if binary { ids.pop() } else { None };
+                    ids.push(self.intern(DagNode::Op {
+                        operator,
+                        left,
+                        right,
+                    }));
+                }
+            }
+        }
+        ids.pop().unwrap()
+    }
+
+    pub fn to_ast(&self, id: NodeId) -> ASTNode<V> {
+        let mut stack = vec![Step::Visit(id)];
+        let mut trees: Vec<ASTNode<V>> = Vec::new();
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Visit(id) => match self.node(id) {
+                    DagNode::Value(v) => trees.push(ASTNode::Value(v.clone())),
+                    DagNode::Op {
+                        operator,
+                        left,
+                        right,
+                    } => {
+                        stack.push(Step::Combine(*operator, left.is_some()));
+                        stack.push(Step::Visit(*right));
+                        if let Some(l) = left {
+                            stack.push(Step::Visit(*l));
+                        }
+                    }
+                },
+                Step::Combine(operator, binary) => {
+                    let right = trees.pop().unwrap();
+                    let left = if binary { trees.pop() } else { None };
+                    trees.push(ASTNode::Op {
+                        operator,
+                        left: left.map(Box::new),
+                        right: Box::new(right),
+                    });
+                }
+            }
+        }
+        trees.pop().unwrap()
+    }
+
+    pub fn size(&self, id: NodeId) -> usize {
+        let mut seen = HashSet::from([id]);
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if let DagNode::Op { left, right, .. } = self.node(id) {
+                for child in left.iter().chain([right]) {
+                    if seen.insert(*child) {
+                        stack.push(*child);
+                    }
+                }
+            }
+        }
+        seen.len()
+    }
+
+    pub fn nnf(&mut self, id: NodeId) -> NodeId {
+        self.solve(Task::Polarity(id, true))
+    }
+
+    pub fn cnf(&mut self, id: NodeId) -> NodeId {
+        let id = self.nnf(id);
+        self.solve(Task::Normal('&', id))
+    }
+
+    pub fn dnf(&mut self, id: NodeId) -> NodeId {
+        let id = self.nnf(id);
+        self.solve(Task::Normal('|', id))
+    }
+
+    fn memo(&self, task: Task) -> Option<NodeId> {
+        match task {
+            Task::Polarity(id, positive) => self.polarity.get(id.0)?[positive as usize],
+            Task::Normal(outer, id) => self.normal.get(id.0)?[(outer == '&') as usize],
+            Task::Distribute(outer, left, right) => {
+                self.distributed.get(&(outer, left, right)).copied()
+            }
+        }
+    }
+
+    fn solve(&mut self, goal: Task) -> NodeId {
+        let mut stack = vec![goal];
+        while let Some(&task) = stack.last() {
+            if self.memo(task).is_some() {
+                stack.pop();
+                continue;
+            }
+            let mut missing = Vec::new();
+            let Some(res) = self.step(task, &mut missing) else {
+                stack.extend(missing);
+                continue;
+            };
+            stack.pop();
+            match task {
+                Task::Polarity(id, positive) => {
+                    slot(&mut self.polarity, id)[positive as usize] = Some(res)
+                }
+                Task::Normal(outer, id) => {
+                    slot(&mut self.normal, id)[(outer == '&') as usize] = Some(res)
+                }
+                Task::Distribute(outer, left, right) => {
+                    self.distributed.insert((outer, left, right), res);
+                }
+            }
+        }
+        self.memo(goal).unwrap()
+    }
+
+    fn lookup<const N: usize>(
+        &self,
+        tasks: [Task; N],
+        missing: &mut Vec<Task>,
+    ) -> Option<[NodeId; N]> {
+        let found = tasks.map(|t| self.memo(t));
+        missing.extend(
+            tasks
+                .iter()
+                .zip(found)
+                .filter_map(|(t, id)| id.is_none().then_some(*t)),
+        );
+        if !missing.is_empty() {
+            return None;
+        }
+        Some(found.map(Option::unwrap))
+    }
+
+    fn step(&mut self, task: Task, missing: &mut Vec<Task>) -> Option<NodeId> {
+        match task {
+            Task::Polarity(id, positive) => self.with_polarity(id, positive, missing),
+            Task::Normal(outer, id) => self.normal_form(id, outer, missing),
+            Task::Distribute(outer, left, right) => self.distribute(outer, left, right, missing),
+        }
+    }
+
+    fn with_polarity(
+        &mut self,
+        id: NodeId,
+        positive: bool,
+        missing: &mut Vec<Task>,
+    ) -> Option<NodeId> {
+        let (operator, left, right) = match self.node(id).clone() {
+            DagNode::Value(_) if positive => return Some(id),
+            DagNode::Value(v) => {
+                return Some(match v.constant() {
+                    Some(value) => self.value(V::from_constant(!value)),
+                    None => self.not(id),
+                })
+            }
+            DagNode::Op {
+                operator: '!',
+                right,
+                ..
+            } => {
+                return self
+                    .lookup([Task::Polarity(right, !positive)], missing)
+                    .map(|[r]| r)
+            }
+            DagNode::Op {
+                operator,
+                left: Some(left),
+                right,
+            } => (operator, left, right),
+            DagNode::Op { .. } => unreachable!(),
+        };
+        let (outer, inner) = if positive { ('&', '|') } else { ('|', '&') };
+        let (l, r) = (
+            Task::Polarity(left, positive),
+            Task::Polarity(right, positive),
+        );
+        let (not_l, not_r) = (
+            Task::Polarity(left, !positive),
+            Task::Polarity(right, !positive),
+        );
+        Some(match operator {
+            '&' | '|' => {
+                let [l, r] = self.lookup([l, r], missing)?;
+                let operator = if positive { operator } else { dual(operator) };
+                self.op(operator, l, r)
+            }
+            '>' => {
+                let [not_l, r] = self.lookup([not_l, r], missing)?;
+                self.op(inner, not_l, r)
+            }
+            '=' => {
+                let [l, r, not_l, not_r] = self.lookup([l, r, not_l, not_r], missing)?;
+                let a = self.op(inner, not_l, r);
+                let b = self.op(inner, not_r, l);
+                self.op(outer, a, b)
+            }
+            _ => {
+                let [l, r, not_l, not_r] = self.lookup([l, r, not_l, not_r], missing)?;
+                let a = self.op(outer, l, not_r);
+                let b = self.op(outer, not_l, r);
+                self.op(inner, a, b)
+            }
+        })
+    }
+
+    fn normal_form(&mut self, id: NodeId, outer: char, missing: &mut Vec<Task>) -> Option<NodeId> {
+        match *self.node(id) {
+            DagNode::Op {
+                operator,
+                left: Some(left),
+                right,
+            } if operator == '&' || operator == '|' => {
+                let [left, right] = self.lookup(
+                    [Task::Normal(outer, left), Task::Normal(outer, right)],
+                    missing,
+                )?;
+                if operator == outer {
+                    Some(self.op(outer, left, right))
+                } else {
+                    self.distribute(outer, left, right, missing)
+                }
+            }
+            _ => Some(id),
+        }
+    }
+
+    fn split(&self, id: NodeId, operator: char) -> Option<(NodeId, NodeId)> {
+        match *self.node(id) {
+            DagNode::Op {
+                operator: op,
+                left: Some(left),
+                right,
+            } if op == operator => Some((left, right)),
+            _ => None,
+        }
+    }
+
+    fn distribute(
+        &mut self,
+        outer: char,
+        left: NodeId,
+        right: NodeId,
+        missing: &mut Vec<Task>,
+    ) -> Option<NodeId> {
+        let (kept, a, b) = if let Some((right_left, right_right)) = self.split(right, outer) {
+            (left, right_left, right_right)
+        } else if let Some((left_left, left_right)) = self.split(left, outer) {
+            (right, left_left, left_right)
+        } else {
+            return Some(self.op(dual(outer), left, right));
+        };
+        let [a, b] = self.lookup(
+            [
+                Task::Distribute(outer, kept, a),
+                Task::Distribute(outer, kept, b),
+            ],
+            missing,
+        )?;
+        Some(self.op(outer, a, b))
+    }
+}
+
+impl Dag<Symbol> {
+    fn clause(&self, id: NodeId) -> Vec<Lit> {
+        let mut clause = Vec::new();
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            match *self.node(id) {
+                DagNode::Value(Symbol::Var(var)) => clause.push(Lit::new(var, true)),
+                DagNode::Op {
+                    operator: '!',
+                    right,
+                    ..
+                } => {
+                    if let DagNode::Value(Symbol::Var(var)) = *self.node(right) {
+                        clause.push(Lit::new(var, false));
+                    }
+                }
+                DagNode::Op {
+                    operator: '|',
+                    left: Some(left),
+                    right,
+                } => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+                _ => {}
+            }
+        }
+        clause
+    }
+
+    pub fn clauses(&self, id: NodeId, clauses: &mut Vec<Vec<Lit>>) {
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            match *self.node(id) {
+                DagNode::Value(Symbol::True) => {}
+                DagNode::Op {
+                    operator: '&',
+                    left: Some(left),
+                    right,
+                } => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+                _ => clauses.push(self.clause(id)),
+            }
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use crate::boolean_evaluation::{fold_constants, ParseError};
+use crate::cdcl::{Lit, MAX_VARS};
+use crate::conjunctive_normal_form::clauses_to_tree;
+use crate::dag::Dag;
+use crate::negation_normal_form::tree_to_string;
+use crate::symbol_table::{build_named_ast, scan_name, SymbolTable};
+use crate::tseitin::{tseitin_clauses, CnfMode};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DimacsError {
+    MissingHeader,
+    InvalidHeader {
+        line: usize,
+    },
+    TooManyVariables {
+        line: usize,
+        declared: usize,
+    },
+    InvalidLiteral {
+        line: usize,
+        token: String,
+    },
+    VariableOutOfRange {
+        line: usize,
+        var: usize,
+        declared: usize,
+    },
+    UnterminatedClause,
+    ClauseCountMismatch {
+        declared: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for DimacsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DimacsError::MissingHeader => write!(f, "missing 'p cnf' header"),
+            DimacsError::InvalidHeader { line } => write!(f, "invalid header on line {}", line),
+            DimacsError::TooManyVariables { line, declared } => write!(
+                f,
+                "header on line {} declares {} variables, more than the {} supported",
+                line, declared, MAX_VARS
+            ),
+            DimacsError::InvalidLiteral { line, token } => {
+                write!(f, "invalid literal '{}' on line {}", token, line)
+            }
+            DimacsError::VariableOutOfRange {
+                line,
+                var,
+                declared,
+            } => write!(
+                f,
+                "variable {} on line {} exceeds the {} declared variables",
+                var, line, declared
+            ),
+            DimacsError::UnterminatedClause => write!(f, "last clause is not terminated by 0"),
+            DimacsError::ClauseCountMismatch { declared, found } => write!(
+                f,
+                "header declares {} clauses but {} were found",
+                declared, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DimacsError {}
+
+fn parse_header(line: &str, number: usize) -> Result<(usize, usize), DimacsError> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    match fields.as_slice() {
+        ["p", "cnf", vars, clauses] => match (vars.parse(), clauses.parse()) {
+            (Ok(vars), Ok(_)) if vars > MAX_VARS => Err(DimacsError::TooManyVariables {
+                line: number,
+                declared: vars,
+            }),
+            (Ok(vars), Ok(clauses)) => Ok((vars, clauses)),
+            _ => Err(DimacsError::InvalidHeader { line: number }),
+        },
+        _ => Err(DimacsError::InvalidHeader { line: number }),
+    }
+}
+
+fn parse_name(line: &str) -> Option<(usize, String)> {
+    let mut fields = line.split_whitespace().skip(1);
+    let var = fields.next()?.parse().ok()?;
+    let name = fields.next()?;
+    let chars: Vec<char> = name.chars().collect();
+    if fields.next().is_some() || scan_name(&chars, 0) != chars.len() {
+        return None;
+    }
+    Some((var, name.to_string()))
+}
+
+pub fn parse_dimacs(input: &str) -> Result<(SymbolTable, Vec<Vec<Lit>>), DimacsError> {
+    let mut names = HashMap::new();
+    let mut header = None;
+    let mut clauses = Vec::new();
+    let mut clause = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let number = i + 1;
+        let line = line.trim();
+        if line.starts_with('%') {
+            break;
+        }
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('c') {
+            if let Some((var, name)) = parse_name(line) {
+                names.insert(var, name);
+            }
+            continue;
+        }
+        if line.starts_with('p') {
+            if header.is_some() {
+                return Err(DimacsError::InvalidHeader { line: number });
+            }
+            header = Some(parse_header(line, number)?);
+            continue;
+        }
+
+        let Some((declared, _)) = header else {
+            return Err(DimacsError::MissingHeader);
+        };
+        for token in line.split_whitespace() {
+            let value: i64 = token.parse().map_err(|_| DimacsError::InvalidLiteral {
+                line: number,
+                token: token.to_string(),
+            })?;
+            if value == 0 {
+                clauses.push(std::mem::take(&mut clause));
+                continue;
+            }
+            let var = value.unsigned_abs() as usize;
+            if var > declared {
+                return Err(DimacsError::VariableOutOfRange {
+                    line: number,
+                    var,
+                    declared,
+                });
+            }
+            clause.push(Lit::new(var - 1, value > 0));
+        }
+    }
+
+    let Some((_, declared_clauses)) = header else {
+        return Err(DimacsError::MissingHeader);
+    };
+    if !clause.is_empty() {
+        return Err(DimacsError::UnterminatedClause);
+    }
+    if clauses.len() != declared_clauses {
+        return Err(DimacsError::ClauseCountMismatch {
+            declared: declared_clauses,
+            found: clauses.len(),
+        });
+    }
+
+    let used = clauses
+        .iter()
+        .flatten()
+        .map(|lit| lit.var() + 1)
+        .max()
+        .unwrap_or(0);
+    let mut table = SymbolTable::new();
+    for var in 1..=used {
+        let name = names
+            .get(&var)
+            .cloned()
+            .unwrap_or_else(|| format!("x{}", var));
+        if table.intern(&name) != var - 1 {
+            table.fresh("x");
+        }
+    }
+    Ok((table, clauses))
+}
+
+pub fn dimacs_to_formula(input: &str) -> Result<String, DimacsError> {
+    let (table, clauses) = parse_dimacs(input)?;
+    Ok(tree_to_string(&table.resolve(&clauses_to_tree(&clauses))))
+}
+
+pub fn write_dimacs(table: &SymbolTable, clauses: &[Vec<Lit>]) -> String {
+    let mut res = String::new();
+    for (id, name) in table.names().enumerate() {
+        res.push_str(&format!("c {} {}\n", id + 1, name));
+    }
+    res.push_str(&format!(
+        "p cnf {} {}\n",
+        table.names().count(),
+        clauses.len()
+    ));
+    for clause in clauses {
+        for lit in clause {
+            let var = lit.var() as i64 + 1;
+            res.push_str(&format!("{} ", if lit.is_positive() { var } else { -var }));
+        }
+        res.push_str("0\n");
+    }
+    res
+}
+
+pub fn formula_to_dimacs(formula: &str, mode: CnfMode) -> Result<String, ParseError> {
+    let mut table = SymbolTable::new();
+    let tree = fold_constants(build_named_ast(formula, &mut table)?);
+
+    let clauses = match mode {
+        CnfMode::Equivalent => {
+            let mut dag = Dag::new();
+            let root = dag.from_ast(&tree);
+            let root = dag.cnf(root);
+            let mut clauses = Vec::new();
+            dag.clauses(root, &mut clauses);
+            clauses
+        }
+        _ => tseitin_clauses(&tree, &mut table, mode == CnfMode::PlaistedGreenbaum),
+    };
+    Ok(write_dimacs(&table, &clauses))
+}
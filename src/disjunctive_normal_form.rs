@@ -0,0 +1,81 @@
+use crate::boolean_evaluation::fold_constants;
+use crate::boolean_evaluation::ASTNode;
+use crate::boolean_evaluation::Atom;
+use crate::boolean_evaluation::ParseError;
+use crate::bytecode::Program;
+use crate::conjunctive_normal_form::fold_right;
+use crate::dag::Dag;
+use crate::negation_normal_form::tree_to_string;
+use crate::symbol_table::{build_named_ast, Symbol, SymbolTable};
+use crate::truth_table::update_dict;
+use std::collections::BTreeMap;
+use std::hash::Hash;
+
+pub fn dnf_tree<V: Atom + Eq + Hash>(tree: ASTNode<V>) -> ASTNode<V> {
+    let mut dag = Dag::new();
+    let root = dag.from_ast(&fold_constants(tree));
+    let root = dag.dnf(root);
+    dag.to_ast(root)
+}
+
+pub fn disjunctive_normal_form(formula: &str) -> Result<String, ParseError> {
+    let mut table = SymbolTable::new();
+    let tree = build_named_ast(formula, &mut table)?;
+
+    Ok(tree_to_string(&table.resolve(&dnf_tree(tree))))
+}
+
+fn canonical_form(formula: &str, minterms: bool) -> Result<String, ParseError> {
+    let mut table = SymbolTable::new();
+    let tree = build_named_ast(formula, &mut table)?;
+
+    let mut dict: BTreeMap<String, bool> = table.names().map(|n| (n.to_string(), false)).collect();
+    let (inner, outer, empty) = if minterms {
+        ('&', '|', Symbol::False)
+    } else {
+        ('|', '&', Symbol::True)
+    };
+    let identity = if minterms {
+        Symbol::True
+    } else {
+        Symbol::False
+    };
+    let ids: Vec<usize> = dict.keys().map(|name| table.intern(name)).collect();
+    let mut terms = Vec::new();
+
+    for (i, result) in Program::compile(&tree, &table)?.rows().enumerate() {
+        if result != minterms {
+            continue;
+        }
+        update_dict(&mut dict, i as i32);
+        let literals = dict
+            .values()
+            .zip(&ids)
+            .map(|(value, id)| {
+                let var = ASTNode::Value(Symbol::Var(*id));
+                if *value == minterms {
+                    var
+                } else {
+                    ASTNode::Op {
+                        operator: '!',
+                        left: None,
+                        right: Box::new(var),
+                    }
+                }
+            })
+            .collect();
+        terms.push(fold_right(literals, inner, identity));
+    }
+
+    Ok(tree_to_string(
+        &table.resolve(&fold_right(terms, outer, empty)),
+    ))
+}
+
+pub fn canonical_dnf(formula: &str) -> Result<String, ParseError> {
+    canonical_form(formula, true)
+}
+
+pub fn canonical_cnf(formula: &str) -> Result<String, ParseError> {
+    canonical_form(formula, false)
+}
@@ -0,0 +1,62 @@
+use crate::boolean_evaluation::{ASTNode, ParseError};
+use crate::sat::tree_assignment;
+use crate::symbol_table::{build_named_ast, Symbol, SymbolTable};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Check {
+    Holds,
+    Counterexample(BTreeMap<String, bool>),
+}
+
+impl Check {
+    pub fn holds(&self) -> bool {
+        *self == Check::Holds
+    }
+}
+
+fn op(operator: char, left: Option<ASTNode<Symbol>>, right: ASTNode<Symbol>) -> ASTNode<Symbol> {
+    ASTNode::Op {
+        operator,
+        left: left.map(Box::new),
+        right: Box::new(right),
+    }
+}
+
+fn refute(tree: &ASTNode<Symbol>, table: SymbolTable) -> Check {
+    match tree_assignment(tree, table) {
+        Some(model) => Check::Counterexample(model),
+        None => Check::Holds,
+    }
+}
+
+fn refute_pair(
+    f: &str,
+    g: &str,
+    combine: impl Fn(ASTNode<Symbol>, ASTNode<Symbol>) -> ASTNode<Symbol>,
+) -> Result<Check, ParseError> {
+    let mut table = SymbolTable::new();
+    let f = build_named_ast(f, &mut table)?;
+    let g = build_named_ast(g, &mut table)?;
+    Ok(refute(&combine(f, g), table))
+}
+
+pub fn equivalent(f: &str, g: &str) -> Result<Check, ParseError> {
+    refute_pair(f, g, |f, g| op('^', Some(f), g))
+}
+
+pub fn entails(f: &str, g: &str) -> Result<Check, ParseError> {
+    refute_pair(f, g, |f, g| op('&', Some(f), op('!', None, g)))
+}
+
+pub fn is_tautology(f: &str) -> Result<Check, ParseError> {
+    let mut table = SymbolTable::new();
+    let f = build_named_ast(f, &mut table)?;
+    Ok(refute(&op('!', None, f), table))
+}
+
+pub fn is_contradiction(f: &str) -> Result<Check, ParseError> {
+    let mut table = SymbolTable::new();
+    let f = build_named_ast(f, &mut table)?;
+    Ok(refute(&f, table))
+}
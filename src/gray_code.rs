@@ -1,3 +1,3 @@
-pub fn gray_code(n: u32) -> u32 {
-    return n ^ (n >> 1);
-}
+pub fn gray_code(n: u32) -> u32 {
+    n ^ (n >> 1)
+}
@@ -0,0 +1,364 @@
+use crate::boolean_evaluation::ASTNode;
+use crate::boolean_evaluation::ParseError;
+use crate::symbol_table::{build_named_ast_with, join_rpn, scan_name, Symbol, SymbolTable};
+use std::fmt::Display;
+
+const OPERATORS: [char; 7] = ['!', '&', '|', '^', '>', '=', '-'];
+
+#[derive(Clone, PartialEq)]
+enum Token {
+    Value(String),
+    Operator(char),
+    Open,
+    Close,
+}
+
+fn tokenize(formula: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let end = scan_name(&chars, i);
+        if end > i {
+            tokens.push((i, Token::Value(chars[i..end].iter().collect())));
+            i = end;
+            continue;
+        }
+        let token = match c {
+            _ if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '0' | '1' => Token::Value(c.to_string()),
+            '⊥' => Token::Value("0".to_string()),
+            '⊤' => Token::Value("1".to_string()),
+            '!' | '¬' => Token::Operator('!'),
+            '&' | '∧' => Token::Operator('&'),
+            '|' | '∨' => Token::Operator('|'),
+            '^' | '⊕' => Token::Operator('^'),
+            '>' | '→' => Token::Operator('>'),
+            '=' | '↔' => Token::Operator('='),
+            '-' if chars.get(i + 1) == Some(&'>') => {
+                tokens.push((i, Token::Operator('>')));
+                i += 2;
+                continue;
+            }
+            '-' | '∖' => Token::Operator('-'),
+            '<' if chars.get(i + 1) == Some(&'-') && chars.get(i + 2) == Some(&'>') => {
+                tokens.push((i, Token::Operator('=')));
+                i += 3;
+                continue;
+            }
+            '(' => Token::Open,
+            ')' => Token::Close,
+            _ => {
+                return Err(ParseError::UnknownSymbol {
+                    offset: i,
+                    symbol: c,
+                })
+            }
+        };
+        tokens.push((i, token));
+        i += 1;
+    }
+
+    Ok(tokens)
+}
+
+fn precedence(operator: char) -> u8 {
+    match operator {
+        '=' => 1,
+        '>' => 2,
+        '|' => 3,
+        '^' => 4,
+        '&' | '-' => 5,
+        '!' => 6,
+        _ => 7,
+    }
+}
+
+enum Pending {
+    Operator(char),
+    Open(usize),
+}
+
+struct Parser<'a, V> {
+    formula: &'a str,
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    leaf: &'a mut dyn FnMut(&str, usize) -> Result<V, ParseError>,
+}
+
+impl<V: Default> Parser<'_, V> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t.clone())
+    }
+
+    fn unexpected(&self) -> ParseError {
+        match self.tokens.get(self.pos) {
+            Some((offset, _)) => ParseError::UnexpectedSymbol {
+                offset: *offset,
+                symbol: self.formula.chars().nth(*offset).unwrap(),
+            },
+            None => ParseError::UnexpectedEnd {
+                offset: self.formula.chars().count(),
+            },
+        }
+    }
+
+    fn reduce(operands: &mut Vec<ASTNode<V>>, operator: char) {
+        let right = Box::new(operands.pop().unwrap());
+        let left = (operator != '!').then(|| Box::new(operands.pop().unwrap()));
+        operands.push(ASTNode::Op {
+            operator,
+            left,
+            right,
+        });
+    }
+
+    fn expression(&mut self) -> Result<ASTNode<V>, ParseError> {
+        let mut operands = Vec::new();
+        let mut pending = Vec::new();
+        let mut depth = 0;
+
+        loop {
+            match self.peek() {
+                Some(Token::Operator('!')) => {
+                    pending.push(Pending::Operator('!'));
+                    self.pos += 1;
+                    continue;
+                }
+                Some(Token::Open) => {
+                    pending.push(Pending::Open(self.tokens[self.pos].0));
+                    depth += 1;
+                    self.pos += 1;
+                    continue;
+                }
+                Some(Token::Value(name)) => {
+                    let offset = self.tokens[self.pos].0;
+                    self.pos += 1;
+                    operands.push(ASTNode::Value((self.leaf)(&name, offset)?));
+                }
+                _ => return Err(self.unexpected()),
+            }
+
+            while depth > 0 && self.peek() == Some(Token::Close) {
+                while let Some(Pending::Operator(operator)) = pending.pop() {
+                    Self::reduce(&mut operands, operator);
+                }
+                depth -= 1;
+                self.pos += 1;
+            }
+
+            match self.peek() {
+                Some(Token::Operator(operator)) if operator != '!' => {
+                    while let Some(&Pending::Operator(top)) = pending.last() {
+                        if precedence(top) < precedence(operator)
+                            || (top == operator && operator == '>')
+                        {
+                            break;
+                        }
+                        pending.pop();
+                        Self::reduce(&mut operands, top);
+                    }
+                    pending.push(Pending::Operator(operator));
+                    self.pos += 1;
+                }
+                None => {
+                    while let Some(entry) = pending.pop() {
+                        match entry {
+                            Pending::Operator(operator) => Self::reduce(&mut operands, operator),
+                            Pending::Open(offset) => {
+                                return Err(ParseError::UnclosedParenthesis { offset })
+                            }
+                        }
+                    }
+                    return Ok(operands.pop().unwrap());
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+}
+
+fn parse_with<V: Default>(
+    formula: &str,
+    leaf: &mut dyn FnMut(&str, usize) -> Result<V, ParseError>,
+) -> Result<ASTNode<V>, ParseError> {
+    let mut parser = Parser {
+        formula,
+        tokens: tokenize(formula)?,
+        pos: 0,
+        leaf,
+    };
+    if parser.tokens.is_empty() {
+        return Err(ParseError::EmptyFormula);
+    }
+
+    parser.expression()
+}
+
+pub fn parse_infix(formula: &str) -> Result<ASTNode<char>, ParseError> {
+    parse_with(formula, &mut |name, offset| {
+        let mut chars = name.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_uppercase() || c == '0' || c == '1' => Ok(c),
+            (Some(c), _) => Err(ParseError::UnknownSymbol { offset, symbol: c }),
+            (None, _) => Err(ParseError::EmptyFormula),
+        }
+    })
+}
+
+pub fn parse_named_infix(
+    formula: &str,
+    table: &mut SymbolTable,
+) -> Result<ASTNode<Symbol>, ParseError> {
+    parse_with(formula, &mut |name, _| {
+        Ok(match name {
+            "0" => Symbol::False,
+            "1" => Symbol::True,
+            _ => Symbol::Var(table.intern(name)),
+        })
+    })
+}
+
+fn push_rpn<V: Default + Display>(node: &ASTNode<V>, tokens: &mut Vec<String>) {
+    let mut stack = vec![Piece::Node(node, false)];
+    while let Some(piece) = stack.pop() {
+        match piece {
+            Piece::Node(ASTNode::Value(v), _) => tokens.push(v.to_string()),
+            Piece::Node(
+                ASTNode::Op {
+                    operator,
+                    left,
+                    right,
+                },
+                _,
+            ) => {
+                stack.push(Piece::Text(operator.to_string()));
+                stack.push(Piece::Node(right, false));
+                if let Some(l) = left.as_ref() {
+                    stack.push(Piece::Node(l, false));
+                }
+            }
+            Piece::Text(text) => tokens.push(text),
+        }
+    }
+}
+
+pub fn tree_to_rpn<V: Default + Display>(node: &ASTNode<V>) -> String {
+    let mut tokens = Vec::new();
+    push_rpn(node, &mut tokens);
+    join_rpn(&tokens)
+}
+
+enum Piece<'a, V: Default> {
+    Node(&'a ASTNode<V>, bool),
+    Text(String),
+}
+
+fn write_infix<V: Default + Display>(
+    node: &ASTNode<V>,
+    symbols: &dyn Fn(&str) -> String,
+    res: &mut String,
+) {
+    let child_level = |n: &ASTNode<V>| match n {
+        ASTNode::Value(_) => precedence('\0'),
+        ASTNode::Op { operator, .. } => precedence(*operator),
+    };
+
+    let mut stack = vec![Piece::Node(node, false)];
+    while let Some(piece) = stack.pop() {
+        let (node, wrap) = match piece {
+            Piece::Node(node, wrap) => (node, wrap),
+            Piece::Text(text) => {
+                res.push_str(&text);
+                continue;
+            }
+        };
+        if wrap {
+            res.push('(');
+            stack.push(Piece::Text(")".to_string()));
+        }
+        match node {
+            ASTNode::Value(v) => res.push_str(&symbols(&v.to_string())),
+            ASTNode::Op {
+                operator,
+                left,
+                right,
+            } => {
+                let level = precedence(*operator);
+                if let Some(l) = left.as_ref() {
+                    let wrap = child_level(right) < level
+                        || (*operator != '>' && child_level(right) == level);
+                    stack.push(Piece::Node(right, wrap));
+                    stack.push(Piece::Text(format!(" {} ", symbols(&operator.to_string()))));
+                    let wrap =
+                        child_level(l) < level || (*operator == '>' && child_level(l) == level);
+                    stack.push(Piece::Node(l, wrap));
+                } else {
+                    res.push_str(&symbols(&operator.to_string()));
+                    stack.push(Piece::Node(right, child_level(right) < level));
+                }
+            }
+        }
+    }
+}
+
+pub fn tree_to_infix<V: Default + Display>(node: &ASTNode<V>) -> String {
+    let mut res = String::new();
+    write_infix(
+        node,
+        &|s| match s {
+            ">" => "->".to_string(),
+            "=" => "<->".to_string(),
+            _ => s.to_string(),
+        },
+        &mut res,
+    );
+    res
+}
+
+pub fn tree_to_unicode<V: Default + Display>(node: &ASTNode<V>) -> String {
+    let mut res = String::new();
+    write_infix(
+        node,
+        &|s| {
+            match s {
+                "0" => "⊥",
+                "1" => "⊤",
+                "!" => "¬",
+                "&" => "∧",
+                "|" => "∨",
+                "^" => "⊕",
+                ">" => "→",
+                "=" => "↔",
+                "-" => "∖",
+                _ => s,
+            }
+            .to_string()
+        },
+        &mut res,
+    );
+    res
+}
+
+pub fn infix_to_rpn(formula: &str) -> Result<String, ParseError> {
+    let mut table = SymbolTable::new();
+    let tree = parse_named_infix(formula, &mut table)?;
+    Ok(tree_to_rpn(&table.resolve(&tree)))
+}
+
+pub fn rpn_to_infix(formula: &str) -> Result<String, ParseError> {
+    let mut table = SymbolTable::new();
+    let tree = build_named_ast_with(formula, &OPERATORS, &mut table)?;
+    Ok(tree_to_infix(&table.resolve(&tree)))
+}
+
+pub fn rpn_to_unicode(formula: &str) -> Result<String, ParseError> {
+    let mut table = SymbolTable::new();
+    let tree = build_named_ast_with(formula, &OPERATORS, &mut table)?;
+    Ok(tree_to_unicode(&table.resolve(&tree)))
+}
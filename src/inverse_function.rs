@@ -1,5 +1,5 @@
 pub fn reverse_map(n: f64) -> (u16, u16) {
-    if n < 0.0 || n > 1.0 {
+    if !(0.0..=1.0).contains(&n) {
         println!("Input must be in the range [0.0, 1.0]");
         return (0, 0);
     }
@@ -13,5 +13,5 @@ pub fn reverse_map(n: f64) -> (u16, u16) {
         y |= ((scaled >> (i * 2 + 1)) & 1) << i;
     }
 
-    return (x as u16, y as u16);
+    (x as u16, y as u16)
 }
@@ -0,0 +1,29 @@
+pub mod adder;
+pub mod bdd;
+pub mod boolean_evaluation;
+pub mod bytecode;
+pub mod cdcl;
+pub mod cli;
+pub mod conjunctive_normal_form;
+pub mod curve;
+pub mod dag;
+pub mod dimacs;
+pub mod disjunctive_normal_form;
+pub mod equivalence;
+pub mod gray_code;
+pub mod infix;
+pub mod inverse_function;
+pub mod minimize;
+pub mod multiplier;
+pub mod negation_normal_form;
+pub mod powerset;
+pub mod repl;
+pub mod sat;
+pub mod set_evaluation;
+pub mod simplify;
+pub mod symbol_table;
+pub mod truth_table;
+pub mod tseitin;
+
+#[cfg(test)]
+mod tests;
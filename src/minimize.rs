@@ -0,0 +1,467 @@
+use crate::boolean_evaluation::ParseError;
+use crate::bytecode::Program;
+use crate::symbol_table::{build_named_ast, join_rpn, SymbolTable};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::iter;
+
+const EXACT_VARS: usize = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Cube {
+    pub care: u64,
+    pub value: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Form {
+    Dnf,
+    Cnf,
+}
+
+fn full_mask(num_vars: usize) -> u64 {
+    if num_vars == 64 {
+        u64::MAX
+    } else {
+        (1 << num_vars) - 1
+    }
+}
+
+impl Cube {
+    pub fn minterm(num_vars: usize, minterm: u64) -> Cube {
+        Cube {
+            care: full_mask(num_vars),
+            value: minterm,
+        }
+    }
+
+    pub fn covers(&self, minterm: u64) -> bool {
+        minterm & self.care == self.value
+    }
+
+    pub fn literals(&self) -> u32 {
+        self.care.count_ones()
+    }
+
+    fn minterms(&self, num_vars: usize) -> impl Iterator<Item = u64> + '_ {
+        let free = full_mask(num_vars) & !self.care;
+        let mut subset = Some(0u64);
+        std::iter::from_fn(move || {
+            let current = subset?;
+            subset = if current == free {
+                None
+            } else {
+                Some(current.wrapping_sub(free) & free)
+            };
+            Some(self.value | current)
+        })
+    }
+}
+
+fn cost(cover: &[Cube]) -> (usize, u32) {
+    (cover.len(), cover.iter().map(|c| c.literals()).sum())
+}
+
+fn prime_implicants(num_vars: usize, minterms: &[u64]) -> Vec<Cube> {
+    let mut current: HashSet<Cube> = minterms
+        .iter()
+        .map(|m| Cube::minterm(num_vars, *m))
+        .collect();
+    let mut primes = Vec::new();
+
+    while !current.is_empty() {
+        let mut next = HashSet::new();
+        let mut merged = HashSet::new();
+        for cube in current.iter() {
+            for k in 0..num_vars {
+                let bit = 1 << k;
+                if cube.care & bit == 0 || cube.value & bit != 0 {
+                    continue;
+                }
+                let partner = Cube {
+                    care: cube.care,
+                    value: cube.value | bit,
+                };
+                if current.contains(&partner) {
+                    next.insert(Cube {
+                        care: cube.care & !bit,
+                        value: cube.value,
+                    });
+                    merged.insert(*cube);
+                    merged.insert(partner);
+                }
+            }
+        }
+        primes.extend(current.difference(&merged));
+        current = next;
+    }
+    primes.sort();
+    primes
+}
+
+fn covering(primes: &[Cube], candidates: &[usize], m: u64) -> Vec<usize> {
+    candidates
+        .iter()
+        .filter(|p| primes[**p].covers(m))
+        .copied()
+        .collect()
+}
+
+fn is_subset(a: &[usize], b: &[usize]) -> bool {
+    a.iter().all(|x| b.contains(x))
+}
+
+fn reduce_chart(
+    primes: &[Cube],
+    mut rows: Vec<u64>,
+    chosen: &mut Vec<usize>,
+) -> (Vec<u64>, Vec<usize>) {
+    let mut candidates: Vec<usize> = (0..primes.len()).collect();
+
+    loop {
+        let before = (rows.len(), candidates.len());
+
+        for m in rows.clone() {
+            if let [essential] = covering(primes, &candidates, m).as_slice() {
+                if !chosen.contains(essential) {
+                    chosen.push(*essential);
+                }
+            }
+        }
+        rows.retain(|m| !chosen.iter().any(|p| primes[*p].covers(*m)));
+        candidates.retain(|p| !chosen.contains(p));
+
+        let covers: Vec<Vec<usize>> = rows
+            .iter()
+            .map(|m| covering(primes, &candidates, *m))
+            .collect();
+        let mut keep = vec![true; rows.len()];
+        for i in 0..rows.len() {
+            for j in 0..rows.len() {
+                if i != j
+                    && keep[j]
+                    && is_subset(&covers[j], &covers[i])
+                    && (covers[i].len() != covers[j].len() || j < i)
+                {
+                    keep[i] = false;
+                    break;
+                }
+            }
+        }
+        let mut kept = keep.iter();
+        rows.retain(|_| *kept.next().unwrap());
+
+        let columns: Vec<Vec<usize>> = candidates
+            .iter()
+            .map(|p| {
+                (0..rows.len())
+                    .filter(|r| primes[*p].covers(rows[*r]))
+                    .collect()
+            })
+            .collect();
+        let mut keep = vec![true; candidates.len()];
+        for i in 0..candidates.len() {
+            for j in 0..candidates.len() {
+                let (p, q) = (primes[candidates[i]], primes[candidates[j]]);
+                if i != j
+                    && keep[j]
+                    && q.literals() <= p.literals()
+                    && is_subset(&columns[i], &columns[j])
+                    && (columns[i].len() != columns[j].len()
+                        || q.literals() < p.literals()
+                        || j < i)
+                {
+                    keep[i] = false;
+                    break;
+                }
+            }
+        }
+        let mut kept = keep.iter();
+        candidates.retain(|_| *kept.next().unwrap());
+
+        if (rows.len(), candidates.len()) == before {
+            return (rows, candidates);
+        }
+    }
+}
+
+fn greedy_cover(primes: &[Cube], candidates: &[usize], rows: &[u64]) -> usize {
+    let mut rows = rows.to_vec();
+    let mut count = 0;
+    while !rows.is_empty() {
+        let best = candidates
+            .iter()
+            .max_by_key(|p| rows.iter().filter(|m| primes[**p].covers(**m)).count())
+            .unwrap();
+        rows.retain(|m| !primes[*best].covers(*m));
+        count += 1;
+    }
+    count
+}
+
+fn petrick(primes: &[Cube], candidates: &[usize], minterms: &[u64]) -> Vec<usize> {
+    let bound = greedy_cover(primes, candidates, minterms);
+    let mut rows: Vec<Vec<usize>> = minterms
+        .iter()
+        .map(|m| covering(primes, candidates, *m))
+        .collect();
+    rows.sort_by_key(|r| r.len());
+    let mut products: Vec<Vec<usize>> = vec![Vec::new()];
+
+    for covering in rows {
+        let mut next = Vec::new();
+        for product in products.iter() {
+            if product.iter().any(|p| covering.contains(p)) {
+                next.push(product.clone());
+                continue;
+            }
+            if product.len() >= bound {
+                continue;
+            }
+            for p in covering.iter() {
+                let mut extended = product.clone();
+                extended.push(*p);
+                extended.sort();
+                next.push(extended);
+            }
+        }
+
+        next.sort_by_key(|p| p.len());
+        next.dedup();
+        products = Vec::new();
+        for product in next {
+            if !products.iter().any(|kept| is_subset(kept, &product)) {
+                products.push(product);
+            }
+        }
+    }
+
+    products
+        .into_iter()
+        .min_by_key(|product| {
+            let cubes: Vec<Cube> = product.iter().map(|p| primes[*p]).collect();
+            cost(&cubes)
+        })
+        .unwrap_or_default()
+}
+
+pub fn quine_mccluskey(num_vars: usize, on: &[u64], dont_care: &[u64]) -> Vec<Cube> {
+    let all: Vec<u64> = on.iter().chain(dont_care.iter()).copied().collect();
+    let primes = prime_implicants(num_vars, &all);
+
+    let mut chosen = Vec::new();
+    let (remaining, candidates) = reduce_chart(&primes, on.to_vec(), &mut chosen);
+    chosen.extend(petrick(&primes, &candidates, &remaining));
+
+    let mut cover: Vec<Cube> = chosen.into_iter().map(|p| primes[p]).collect();
+    cover.sort_by_key(|c| Reverse((c.care, c.value)));
+    cover
+}
+
+fn expand(num_vars: usize, mut cover: Vec<Cube>, allowed: &[bool]) -> Vec<Cube> {
+    cover.sort_by_key(|c| c.literals());
+    let mut expanded: Vec<Cube> = Vec::new();
+
+    for mut cube in cover {
+        if expanded
+            .iter()
+            .any(|e| cube.care & e.care == e.care && cube.value & e.care == e.value)
+        {
+            continue;
+        }
+        for k in (0..num_vars).rev() {
+            let bit = 1 << k;
+            if cube.care & bit == 0 {
+                continue;
+            }
+            let other_half = Cube {
+                care: cube.care,
+                value: cube.value ^ bit,
+            };
+            if other_half.minterms(num_vars).all(|m| allowed[m as usize]) {
+                cube = Cube {
+                    care: cube.care & !bit,
+                    value: cube.value & !bit,
+                };
+            }
+        }
+        expanded.push(cube);
+    }
+    expanded
+}
+
+fn coverage(num_vars: usize, cover: &[Cube], on: &[bool]) -> Vec<u32> {
+    let mut counts = vec![0; on.len()];
+    for cube in cover {
+        for m in cube.minterms(num_vars) {
+            counts[m as usize] += 1;
+        }
+    }
+    counts
+}
+
+fn irredundant(num_vars: usize, mut cover: Vec<Cube>, on: &[bool]) -> Vec<Cube> {
+    let mut counts = coverage(num_vars, &cover, on);
+    cover.sort_by_key(|c| Reverse(c.literals()));
+
+    cover.retain(|cube| {
+        let redundant = cube
+            .minterms(num_vars)
+            .all(|m| !on[m as usize] || counts[m as usize] > 1);
+        if redundant {
+            for m in cube.minterms(num_vars) {
+                counts[m as usize] -= 1;
+            }
+        }
+        !redundant
+    });
+    cover
+}
+
+fn reduce(num_vars: usize, cover: Vec<Cube>, on: &[bool]) -> Vec<Cube> {
+    let mut counts = coverage(num_vars, &cover, on);
+    let mut reduced = Vec::new();
+
+    for cube in cover {
+        let unique: Vec<u64> = cube
+            .minterms(num_vars)
+            .filter(|m| on[*m as usize] && counts[*m as usize] == 1)
+            .collect();
+        for m in cube.minterms(num_vars) {
+            counts[m as usize] -= 1;
+        }
+        let Some(first) = unique.first() else {
+            continue;
+        };
+        let differ = unique.iter().fold(0, |acc, m| acc | (m ^ first));
+        let care = full_mask(num_vars) & !differ;
+        let smaller = Cube {
+            care,
+            value: first & care,
+        };
+        for m in smaller.minterms(num_vars) {
+            counts[m as usize] += 1;
+        }
+        reduced.push(smaller);
+    }
+    reduced
+}
+
+pub fn espresso(num_vars: usize, on: &[u64], dont_care: &[u64]) -> Vec<Cube> {
+    let mut is_on = vec![false; 1 << num_vars];
+    let mut allowed = vec![false; 1 << num_vars];
+    for m in on {
+        is_on[*m as usize] = true;
+        allowed[*m as usize] = true;
+    }
+    for m in dont_care {
+        allowed[*m as usize] = true;
+    }
+
+    let mut cover: Vec<Cube> = on.iter().map(|m| Cube::minterm(num_vars, *m)).collect();
+    let mut best: Option<Vec<Cube>> = None;
+    loop {
+        cover = expand(num_vars, cover, &allowed);
+        cover = irredundant(num_vars, cover, &is_on);
+        if let Some(best) = best.as_ref() {
+            if cost(&cover) >= cost(best) {
+                break;
+            }
+        }
+        best = Some(cover.clone());
+        cover = reduce(num_vars, cover, &is_on);
+    }
+
+    let mut cover = best.unwrap();
+    cover.sort_by_key(|c| Reverse((c.care, c.value)));
+    cover
+}
+
+pub fn minimize_table(num_vars: usize, on: &[u64], dont_care: &[u64]) -> Vec<Cube> {
+    if num_vars <= EXACT_VARS {
+        quine_mccluskey(num_vars, on, dont_care)
+    } else {
+        espresso(num_vars, on, dont_care)
+    }
+}
+
+pub fn cover_to_rpn(cover: &[Cube], names: &[String], form: Form) -> String {
+    let (inner, outer, negated, empty, full) = match form {
+        Form::Dnf => ("&", "|", false, "0", "1"),
+        Form::Cnf => ("|", "&", true, "1", "0"),
+    };
+    let mut tokens = Vec::new();
+
+    for cube in cover {
+        let mut literals = 0;
+        for (k, name) in names.iter().enumerate() {
+            let bit = 1 << (names.len() - 1 - k);
+            if cube.care & bit == 0 {
+                continue;
+            }
+            tokens.push(name.clone());
+            if (cube.value & bit != 0) == negated {
+                tokens.push("!".to_string());
+            }
+            literals += 1;
+        }
+        if literals == 0 {
+            tokens.push(full.to_string());
+        }
+        for _ in 1..literals {
+            tokens.push(inner.to_string());
+        }
+    }
+    if cover.is_empty() {
+        tokens.push(empty.to_string());
+    }
+    for _ in 1..cover.len() {
+        tokens.push(outer.to_string());
+    }
+    join_rpn(&tokens)
+}
+
+pub fn minimize(formula: &str, dont_care: Option<&str>, form: Form) -> Result<String, ParseError> {
+    let mut table = SymbolTable::new();
+    let tree = build_named_ast(formula, &mut table)?;
+    let dont_care = match dont_care {
+        Some(dc) => Some(build_named_ast(dc, &mut table)?),
+        None => None,
+    };
+
+    let mut names: Vec<String> = table.names().map(|n| n.to_string()).collect();
+    names.sort();
+    let program = Program::compile(&tree, &table)?;
+    let dont_care = match dont_care {
+        Some(dc) => Some(Program::compile(&dc, &table)?),
+        None => None,
+    };
+    let dc_rows = dont_care
+        .iter()
+        .flat_map(|dc| dc.rows())
+        .chain(iter::repeat(false));
+    let mut on = Vec::new();
+    let mut dc = Vec::new();
+
+    for ((result, dont_care), i) in program.rows().zip(dc_rows).zip(0..) {
+        if dont_care {
+            dc.push(i);
+        } else if result == (form == Form::Dnf) {
+            on.push(i);
+        }
+    }
+
+    Ok(cover_to_rpn(
+        &minimize_table(names.len(), &on, &dc),
+        &names,
+        form,
+    ))
+}
+
+pub fn minimal_dnf(formula: &str) -> Result<String, ParseError> {
+    minimize(formula, None, Form::Dnf)
+}
+
+pub fn minimal_cnf(formula: &str) -> Result<String, ParseError> {
+    minimize(formula, None, Form::Cnf)
+}
@@ -1,160 +1,252 @@
-use crate::boolean_evaluation::build_ast;
+use crate::boolean_evaluation::fold_constants;
 use crate::boolean_evaluation::ASTNode;
+use crate::boolean_evaluation::Atom;
+use crate::boolean_evaluation::ParseError;
+use crate::symbol_table::{build_named_ast, join_rpn, SymbolTable};
+use std::fmt::Display;
 use std::mem;
 
-pub fn tree_to_string(node: &ASTNode<char>) -> String {
-    match node {
-        ASTNode::Value(v) => v.to_string(),
-        ASTNode::Op {
-            operator,
-            left,
-            right,
-        } => {
-            let mut res = String::new();
+enum Token<'a, V: Default> {
+    Node(&'a ASTNode<V>),
+    Operator(char),
+}
 
-            if *operator == '&' || *operator == '|' {
-                res.push_str(&tree_to_string(right));
-            }
-            if let Some(l) = left.as_ref() {
-                res.push_str(&tree_to_string(l));
-            }
-            if *operator != '&' && *operator != '|' {
-                res.push_str(&tree_to_string(right));
+fn push_tokens<V: Default + Display>(node: &ASTNode<V>, tokens: &mut Vec<String>) {
+    let mut stack = vec![Token::Node(node)];
+    while let Some(token) = stack.pop() {
+        match token {
+            Token::Node(ASTNode::Value(v)) => tokens.push(v.to_string()),
+            Token::Node(ASTNode::Op {
+                operator,
+                left,
+                right,
+            }) => {
+                stack.push(Token::Operator(*operator));
+                if *operator != '&' && *operator != '|' {
+                    stack.push(Token::Node(right));
+                }
+                if let Some(l) = left.as_ref() {
+                    stack.push(Token::Node(l));
+                }
+                if *operator == '&' || *operator == '|' {
+                    stack.push(Token::Node(right));
+                }
             }
-            res.push_str(&operator.to_string());
-
-            res
+            Token::Operator(operator) => tokens.push(operator.to_string()),
         }
     }
 }
 
-pub fn tree_to_almost_nnf(node: &mut ASTNode<char>, modified: &mut bool) {
-    match node {
-        ASTNode::Op {
-            operator,
-            left,
-            right,
-        } => {
-            match operator {
-                '>' => {
+pub fn tree_to_string<V: Default + Display>(node: &ASTNode<V>) -> String {
+    let mut tokens = Vec::new();
+    push_tokens(node, &mut tokens);
+    join_rpn(&tokens)
+}
+
+pub fn tree_to_almost_nnf<V: Atom>(node: &mut ASTNode<V>, modified: &mut bool) {
+    if let ASTNode::Op {
+        operator,
+        left,
+        right,
+    } = node
+    {
+        match operator {
+            '>' => {
+                *node = ASTNode::Op {
+                    operator: '|',
+                    left: Some(Box::new(ASTNode::Op {
+                        operator: '!',
+                        left: None,
+                        right: left.take().unwrap(),
+                    })),
+                    right: Box::new(mem::replace(right, ASTNode::Value(V::default()))),
+                };
+                *modified = true;
+            }
+            '=' => {
+                *node = ASTNode::Op {
+                    operator: '&',
+                    left: Some(Box::new(ASTNode::Op {
+                        operator: '>',
+                        left: left.clone(),
+                        right: right.clone(),
+                    })),
+                    right: Box::new(ASTNode::Op {
+                        operator: '>',
+                        left: Some(Box::new(mem::replace(right, ASTNode::Value(V::default())))),
+                        right: left.take().unwrap(),
+                    }),
+                };
+                *modified = true;
+            }
+            '!' => match &mut **right {
+                ASTNode::Op {
+                    operator: '!',
+                    right: right_right,
+                    ..
+                } => {
+                    *node = mem::replace(right_right, ASTNode::Value(V::default()));
+                    *modified = true;
+                    tree_to_almost_nnf(node, modified);
+                    return;
+                }
+                ASTNode::Op {
+                    operator,
+                    right: right_right,
+                    left: right_left,
+                } if *operator == '|' || *operator == '&' => {
                     *node = ASTNode::Op {
-                        operator: '|',
+                        operator: if *operator == '|' { '&' } else { '|' },
                         left: Some(Box::new(ASTNode::Op {
                             operator: '!',
                             left: None,
-                            right: left.take().unwrap(),
+                            right: right_left.take().unwrap(),
                         })),
-                        right: Box::new(mem::replace(right, ASTNode::Value('\0'))),
+                        right: Box::new(ASTNode::Op {
+                            operator: '!',
+                            left: None,
+                            right: Box::new(mem::replace(
+                                right_right,
+                                ASTNode::Value(V::default()),
+                            )),
+                        }),
                     };
                     *modified = true;
                 }
-                '=' => {
-                    *node = ASTNode::Op {
+                _ => {}
+            },
+            '^' => {
+                *node = ASTNode::Op {
+                    operator: '|',
+                    left: Some(Box::new(ASTNode::Op {
                         operator: '&',
-                        left: Some(Box::new(ASTNode::Op {
-                            operator: '>',
-                            left: left.clone(),
-                            right: right.clone(),
-                        })),
+                        left: left.clone(),
                         right: Box::new(ASTNode::Op {
-                            operator: '>',
-                            left: Some(Box::new(mem::replace(right, ASTNode::Value('\0')))),
-                            right: left.take().unwrap(),
+                            operator: '!',
+                            left: None,
+                            right: right.clone(),
                         }),
-                    };
-                    *modified = true;
-                }
-                '!' => match &mut **right {
-                    ASTNode::Op {
-                        operator: '!',
-                        right: right_right,
-                        ..
-                    } => {
-                        *node = mem::replace(right_right, ASTNode::Value('\0'));
-                        *modified = true;
-                        tree_to_almost_nnf(node, modified);
-                        return;
-                    }
-                    ASTNode::Op {
-                        operator,
-                        right: right_right,
-                        left: right_left,
-                    } if *operator == '|' || *operator == '&' => {
-                        *node = ASTNode::Op {
-                            operator: if *operator == '|' { '&' } else { '|' },
-                            left: Some(Box::new(ASTNode::Op {
-                                operator: '!',
-                                left: None,
-                                right: right_left.take().unwrap(),
-                            })),
-                            right: Box::new(ASTNode::Op {
-                                operator: '!',
-                                left: None,
-                                right: Box::new(mem::replace(right_right, ASTNode::Value('\0'))),
-                            }),
-                        };
-                        *modified = true;
-                    }
-                    _ => {}
-                },
-                '^' => {
-                    *node = ASTNode::Op {
-                        operator: '|',
+                    })),
+                    right: Box::new(ASTNode::Op {
+                        operator: '&',
                         left: Some(Box::new(ASTNode::Op {
-                            operator: '&',
-                            left: left.clone(),
-                            right: Box::new(ASTNode::Op {
-                                operator: '!',
-                                left: None,
-                                right: right.clone(),
-                            }),
+                            operator: '!',
+                            left: None,
+                            right: left.take().unwrap(),
                         })),
-                        right: Box::new(ASTNode::Op {
-                            operator: '&',
-                            left: Some(Box::new(ASTNode::Op {
-                                operator: '!',
-                                left: None,
-                                right: left.take().unwrap(),
-                            })),
-                            right: Box::new(mem::replace(right, ASTNode::Value('\0'))),
-                        }),
-                    };
-                    *modified = true;
-                }
-                _ => {}
+                        right: Box::new(mem::replace(right, ASTNode::Value(V::default()))),
+                    }),
+                };
+                *modified = true;
             }
+            _ => {}
+        }
 
-            let ASTNode::Op { left, right, .. } = node else {
-                return;
-            };
-            if let Some(l) = left.as_mut() {
-                tree_to_almost_nnf(l, modified);
-            }
-            tree_to_almost_nnf(right, modified);
+        let ASTNode::Op { left, right, .. } = node else {
+            return;
+        };
+        if let Some(l) = left.as_mut() {
+            tree_to_almost_nnf(l, modified);
         }
-        _ => {}
+        tree_to_almost_nnf(right, modified);
     }
 }
 
-#[allow(non_snake_case)]
-pub fn negation_normal_form(formula: &str) -> String {
-    if formula.contains('1') || formula.contains('0') {
-        println!("Formula contains constants (0 or 1), cannot convert to NNF");
-        return String::new();
+fn take<V: Atom>(node: &mut ASTNode<V>) -> ASTNode<V> {
+    mem::replace(node, ASTNode::Value(V::default()))
+}
+
+fn op<V: Default>(operator: char, left: ASTNode<V>, right: ASTNode<V>) -> ASTNode<V> {
+    ASTNode::Op {
+        operator,
+        left: Some(Box::new(left)),
+        right: Box::new(right),
+    }
+}
+
+fn operands<V: Default>(node: &mut ASTNode<V>) -> Vec<&mut ASTNode<V>> {
+    match node {
+        ASTNode::Value(_) => vec![],
+        ASTNode::Op { left, right, .. } => left
+            .as_deref_mut()
+            .into_iter()
+            .chain([&mut **right])
+            .collect(),
     }
-    let mut tree = match build_ast(formula) {
-        Some(ast) => ast,
-        None => {
-            println!("Error in formula");
-            return String::new();
+}
+
+fn with_polarity<V: Atom>(node: &mut ASTNode<V>, positive: bool) {
+    let mut stack = vec![(node, positive)];
+    while let Some((node, positive)) = stack.pop() {
+        let (operator, unary) = match &*node {
+            ASTNode::Value(_) => {
+                if !positive {
+                    let value = take(node);
+                    *node = ASTNode::Op {
+                        operator: '!',
+                        left: None,
+                        right: Box::new(value),
+                    };
+                }
+                continue;
+            }
+            ASTNode::Op { operator, left, .. } => (*operator, left.is_none()),
+        };
+        if unary {
+            let child = take(operands(node).pop().unwrap());
+            *node = child;
+            stack.push((node, !positive));
+            continue;
         }
-    };
 
-    let mut modified = true;
-    while modified {
-        modified = false;
-        tree_to_almost_nnf(&mut tree, &mut modified);
+        let (outer, inner) = if positive { ('&', '|') } else { ('|', '&') };
+        let polarities = match operator {
+            '&' | '|' | '>' => {
+                let replacement = match operator {
+                    '>' => inner,
+                    _ if positive => operator,
+                    '&' => '|',
+                    _ => '&',
+                };
+                if let ASTNode::Op { operator, .. } = node {
+                    *operator = replacement;
+                }
+                vec![positive ^ (operator == '>'), positive]
+            }
+            '=' | '^' => {
+                let mut children = operands(node);
+                let r = take(children.pop().unwrap());
+                let l = take(children.pop().unwrap());
+                let (not_l, not_r) = (l.clone(), r.clone());
+                *node = if operator == '=' {
+                    op(outer, op(inner, not_l, r), op(inner, not_r, l))
+                } else {
+                    op(inner, op(outer, l, not_r), op(outer, not_l, r))
+                };
+                let grandchildren = operands(node).into_iter().flat_map(operands);
+                let polarities = if operator == '=' {
+                    [!positive, positive, !positive, positive]
+                } else {
+                    [positive, !positive, !positive, positive]
+                };
+                stack.extend(grandchildren.zip(polarities));
+                continue;
+            }
+            _ => continue,
+        };
+        stack.extend(operands(node).into_iter().zip(polarities));
     }
+}
+
+pub fn nnf_tree<V: Atom>(mut tree: ASTNode<V>) -> ASTNode<V> {
+    with_polarity(&mut tree, true);
+    tree
+}
+
+#[allow(non_snake_case)]
+pub fn negation_normal_form(formula: &str) -> Result<String, ParseError> {
+    let mut table = SymbolTable::new();
+    let tree = fold_constants(build_named_ast(formula, &mut table)?);
 
-    tree_to_string(&tree)
+    Ok(tree_to_string(&table.resolve(&nnf_tree(tree))))
 }
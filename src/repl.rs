@@ -0,0 +1,305 @@
+use crate::boolean_evaluation::{eval_formula_with, ASTNode, ParseError};
+use crate::cli::{from_rpn, render_error, Format};
+use crate::conjunctive_normal_form::conjunctive_normal_form;
+use crate::equivalence::{equivalent, Check};
+use crate::infix::{parse_named_infix, tree_to_rpn};
+use crate::negation_normal_form::negation_normal_form;
+use crate::sat::sat_assignment;
+use crate::symbol_table::{build_named_ast, scan_name, SymbolTable};
+use crate::truth_table::format_truth_table;
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub const COMMANDS: [&str; 10] = [
+    ":table", ":nnf", ":cnf", ":sat", ":equiv", ":format", ":defs", ":undef", ":help", ":quit",
+];
+
+const HELP: &str = "name := formula   define a formula, usable by name in later formulas
+formula           evaluate a closed formula, or expand the definitions of an open one
+:table f          print the truth table of f
+:nnf f            print the negation normal form of f
+:cnf f            print the conjunctive normal form of f
+:sat f            print a satisfying assignment of f
+:equiv f, g       check whether f and g are equivalent
+:format n         switch notation to rpn, infix or unicode
+:defs             list the defined formulas
+:undef name       remove a definition
+:quit             leave the shell";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReplError {
+    Parse { formula: String, error: ParseError },
+    Usage(String),
+}
+
+impl fmt::Display for ReplError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplError::Parse { formula, error } => {
+                write!(
+                    f,
+                    "{}",
+                    render_error(formula, formula, error, error.offset(), false)
+                )
+            }
+            ReplError::Usage(msg) => write!(f, "error: {}", msg),
+        }
+    }
+}
+
+fn parse_error(formula: &str) -> impl Fn(ParseError) -> ReplError + '_ {
+    move |error| ReplError::Parse {
+        formula: formula.to_string(),
+        error,
+    }
+}
+
+fn format_model(model: &BTreeMap<String, bool>) -> String {
+    let values: Vec<String> = model
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, *v as u8))
+        .collect();
+    values.join(" ")
+}
+
+#[derive(Default)]
+pub struct Repl {
+    pub format: Format,
+    definitions: BTreeMap<String, ASTNode<String>>,
+}
+
+impl Repl {
+    pub fn new(format: Format) -> Self {
+        Repl {
+            format,
+            ..Default::default()
+        }
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.definitions.keys().map(|n| n.as_str())
+    }
+
+    fn expand(&self, node: &ASTNode<String>) -> ASTNode<String> {
+        node.fold(
+            |name| {
+                self.definitions
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| ASTNode::Value(name.clone()))
+            },
+            |operator, left, right| ASTNode::Op {
+                operator,
+                left: left.map(Box::new),
+                right: Box::new(right),
+            },
+        )
+    }
+
+    fn parse(&self, formula: &str) -> Result<ASTNode<String>, ReplError> {
+        let mut table = SymbolTable::new();
+        let tree = match self.format {
+            Format::Rpn => build_named_ast(formula, &mut table),
+            Format::Infix | Format::Unicode => parse_named_infix(formula, &mut table),
+        }
+        .map_err(parse_error(formula))?;
+        Ok(self.expand(&table.resolve(&tree)))
+    }
+
+    fn parse_rpn(&self, formula: &str) -> Result<String, ReplError> {
+        Ok(tree_to_rpn(&self.parse(formula)?))
+    }
+
+    fn show(&self, rpn: &str) -> String {
+        from_rpn(rpn, self.format)
+    }
+
+    fn split_pair<'a>(&self, args: &'a str) -> Result<(&'a str, &'a str), ReplError> {
+        if let Some((f, g)) = args.split_once(',') {
+            return Ok((f.trim(), g.trim()));
+        }
+        match args.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [f, g] => Ok((f, g)),
+            _ => Err(ReplError::Usage(
+                "expected two formulas separated by ','".to_string(),
+            )),
+        }
+    }
+
+    fn equiv(&self, args: &str) -> Result<String, ReplError> {
+        let (f, g) = self.split_pair(args)?;
+        let (f, g) = (self.parse_rpn(f)?, self.parse_rpn(g)?);
+        match equivalent(&f, &g).map_err(parse_error(&f))? {
+            Check::Holds => Ok("equivalent".to_string()),
+            Check::Counterexample(model) => {
+                Ok(format!("not equivalent, e.g. {}", format_model(&model)))
+            }
+        }
+    }
+
+    fn run(
+        &self,
+        args: &str,
+        command: fn(&str) -> Result<String, ParseError>,
+    ) -> Result<String, ReplError> {
+        let rpn = self.parse_rpn(args)?;
+        command(&rpn).map_err(parse_error(&rpn))
+    }
+
+    pub fn eval_line(&mut self, line: &str) -> Result<String, ReplError> {
+        let line = line.trim();
+        if let Some((name, formula)) = line.split_once(":=") {
+            let name = name.trim();
+            let chars: Vec<char> = name.chars().collect();
+            if chars.is_empty()
+                || scan_name(&chars, 0) != chars.len()
+                || name.starts_with(|c: char| c.is_ascii_uppercase())
+            {
+                return Err(ReplError::Usage(format!("invalid name '{}'", name)));
+            }
+            let tree = self.parse(formula.trim())?;
+            let res = self.show(&tree_to_rpn(&tree));
+            self.definitions.insert(name.to_string(), tree);
+            return Ok(format!("{} := {}", name, res));
+        }
+
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+        let args = args.trim();
+        match command {
+            "" => Ok(String::new()),
+            ":help" => Ok(HELP.to_string()),
+            ":table" => self.run(args, format_truth_table),
+            ":nnf" => Ok(self.show(&self.run(args, negation_normal_form)?)),
+            ":cnf" => Ok(self.show(&self.run(args, conjunctive_normal_form)?)),
+            ":sat" => {
+                let rpn = self.parse_rpn(args)?;
+                Ok(sat_assignment(&rpn)
+                    .map_err(parse_error(&rpn))?
+                    .map_or("unsatisfiable".to_string(), |m| format_model(&m)))
+            }
+            ":equiv" => self.equiv(args),
+            ":format" => {
+                self.format = match args {
+                    "rpn" => Format::Rpn,
+                    "infix" => Format::Infix,
+                    "unicode" => Format::Unicode,
+                    _ => return Err(ReplError::Usage(format!("unknown format '{}'", args))),
+                };
+                Ok(String::new())
+            }
+            ":defs" => {
+                let defs: Vec<String> = self
+                    .definitions
+                    .iter()
+                    .map(|(name, tree)| format!("{} := {}", name, self.show(&tree_to_rpn(tree))))
+                    .collect();
+                Ok(defs.join("\n"))
+            }
+            ":undef" => match self.definitions.remove(args) {
+                Some(_) => Ok(String::new()),
+                None => Err(ReplError::Usage(format!("'{}' is not defined", args))),
+            },
+            _ if command.starts_with(':') => {
+                Err(ReplError::Usage(format!("unknown command '{}'", command)))
+            }
+            _ => {
+                let rpn = self.parse_rpn(line)?;
+                match eval_formula_with(&rpn, &BTreeMap::new()) {
+                    Ok(value) => Ok(value.to_string()),
+                    Err(ParseError::UnboundVariable { .. }) => Ok(self.show(&rpn)),
+                    Err(e) => Err(parse_error(&rpn)(e)),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct ReplHelper {
+    names: Vec<String>,
+}
+
+pub fn complete(names: &[String], line: &str, pos: usize) -> (usize, Vec<String>) {
+    let start = line[..pos]
+        .char_indices()
+        .rev()
+        .find(|(_, c)| !(c.is_alphanumeric() || "_[].:".contains(*c)))
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let word = &line[start..pos];
+    let candidates = if word.starts_with(':') && start == 0 {
+        COMMANDS
+            .iter()
+            .filter(|c| c.starts_with(word))
+            .map(|c| c.to_string())
+            .collect()
+    } else if word.is_empty() {
+        Vec::new()
+    } else {
+        names
+            .iter()
+            .filter(|n| n.starts_with(word))
+            .cloned()
+            .collect()
+    };
+    (start, candidates)
+}
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(complete(&self.names, line, pos))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+pub fn run_repl(format: Format) -> Result<(), ReadlineError> {
+    let mut editor: Editor<ReplHelper, DefaultHistory> = Editor::new()?;
+    editor.set_helper(Some(ReplHelper::default()));
+    let mut repl = Repl::new(format);
+
+    loop {
+        let line = match editor.readline("> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        if !line.trim().is_empty() {
+            editor.add_history_entry(line.as_str())?;
+        }
+        if line.trim() == ":quit" {
+            return Ok(());
+        }
+
+        match repl.eval_line(&line) {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => println!("{}", output.trim_end()),
+            Err(err) => eprintln!("{}", err),
+        }
+        if let Some(helper) = editor.helper_mut() {
+            helper.names = repl.names().map(|n| n.to_string()).collect();
+        }
+    }
+}
@@ -1,33 +1,113 @@
-use crate::boolean_evaluation::build_ast;
-use crate::boolean_evaluation::eval_node;
-use crate::truth_table::create_dict;
-use crate::truth_table::substitute_vars;
-use crate::truth_table::update_dict;
-
-pub fn sat(formula: &str) -> bool {
-    if formula.contains('1') || formula.contains('0') {
-        println!("Formula contains constants (0 or 1), cannot perform SAT");
-        return false;
+use crate::bdd::BddManager;
+use crate::boolean_evaluation::{fold_constants, ASTNode, ParseError};
+use crate::bytecode::Program;
+use crate::cdcl::{Lit, Solver};
+use crate::symbol_table::{build_named_ast, Symbol, SymbolTable};
+use crate::tseitin::tseitin_clauses;
+use std::collections::BTreeMap;
+
+const BRUTE_FORCE_VARS: usize = 16;
+
+fn load_tree(tree: &ASTNode<Symbol>, mut table: SymbolTable) -> (Solver, SymbolTable, usize) {
+    let vars = table.names().count();
+    let clauses = tseitin_clauses(tree, &mut table, true);
+
+    let mut solver = Solver::new(table.names().count());
+    for clause in clauses.iter() {
+        solver.add_clause(clause);
     }
-    if build_ast(formula).is_none() {
-        println!("Error in formula");
-        return false;
+    (solver, table, vars)
+}
+
+fn parse(formula: &str) -> Result<(ASTNode<Symbol>, SymbolTable), ParseError> {
+    let mut table = SymbolTable::new();
+    let tree = fold_constants(build_named_ast(formula, &mut table)?);
+    Ok((tree, table))
+}
+
+fn brute_force(tree: &ASTNode<Symbol>, table: &SymbolTable) -> Option<Program> {
+    if table.names().count() > BRUTE_FORCE_VARS {
+        return None;
     }
+    Program::compile(tree, table).ok()
+}
+
+fn row_assignment(program: &Program, table: &SymbolTable, row: u64) -> BTreeMap<String, bool> {
+    let mut names: Vec<String> = table.names().map(|n| n.to_string()).collect();
+    names.sort();
+    names.into_iter().zip(program.row(row)).collect()
+}
 
-    let mut dict = create_dict(formula);
-    if dict.is_empty() {
-        println!("No variables in formula");
-        return false;
+fn assignment(table: &SymbolTable, model: &[bool], vars: usize) -> BTreeMap<String, bool> {
+    table
+        .names()
+        .take(vars)
+        .enumerate()
+        .map(|(id, name)| (name.to_string(), model[id]))
+        .collect()
+}
+
+pub fn sat(formula: &str) -> Result<bool, ParseError> {
+    let (tree, table) = parse(formula)?;
+    if let Some(program) = brute_force(&tree, &table) {
+        return Ok(program.first_model().is_some());
     }
+    let (mut solver, _, _) = load_tree(&tree, table);
+    Ok(solver.solve().is_some())
+}
 
-    for i in 0..(1 << dict.len()) {
-        update_dict(&mut dict, i);
+pub fn sat_assignment(formula: &str) -> Result<Option<BTreeMap<String, bool>>, ParseError> {
+    let (tree, table) = parse(formula)?;
+    if let Some(program) = brute_force(&tree, &table) {
+        return Ok(program
+            .first_model()
+            .map(|row| row_assignment(&program, &table, row)));
+    }
+    Ok(tree_assignment(&tree, table))
+}
+
+pub fn tree_assignment(
+    tree: &ASTNode<Symbol>,
+    table: SymbolTable,
+) -> Option<BTreeMap<String, bool>> {
+    let (mut solver, table, vars) = load_tree(tree, table);
+    solver.solve().map(|model| assignment(&table, &model, vars))
+}
+
+pub struct Models {
+    solver: Solver,
+    table: SymbolTable,
+    vars: usize,
+}
+
+impl Iterator for Models {
+    type Item = BTreeMap<String, bool>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let model = self.solver.solve()?;
+        let blocking: Vec<Lit> = (0..self.vars).map(|v| Lit::new(v, !model[v])).collect();
+        self.solver.add_clause(&blocking);
+        Some(assignment(&self.table, &model, self.vars))
+    }
+}
+
+pub fn models(formula: &str) -> Result<Models, ParseError> {
+    let (tree, table) = parse(formula)?;
+    let (solver, table, vars) = load_tree(&tree, table);
+    Ok(Models {
+        solver,
+        table,
+        vars,
+    })
+}
 
-        let new_formula = substitute_vars(&formula, &dict);
-        let tree = build_ast(&new_formula).unwrap();
-        if eval_node(&tree).unwrap() {
-            return true;
-        }
+pub fn count_models(formula: &str) -> Result<Option<u128>, ParseError> {
+    let (tree, table) = parse(formula)?;
+    if let Some(program) = brute_force(&tree, &table) {
+        return Ok(Some(program.count() as u128));
     }
-    false
+    let order: Vec<usize> = (0..table.names().count()).collect();
+    let mut manager = BddManager::with_order(&order);
+    let root = manager.from_named_ast(&tree);
+    Ok(manager.model_count(root))
 }
@@ -1,145 +1,729 @@
-use crate::boolean_evaluation::{build_ast, ASTNode};
-use crate::negation_normal_form::tree_to_almost_nnf;
-use once_cell::sync::Lazy;
-use std::ops::{BitAnd, BitOr, Not};
-use std::process::exit;
-use std::sync::Mutex;
+use crate::boolean_evaluation::{parse_rpn_with, ASTNode, Atom, ParseError};
+use crate::symbol_table::{build_named_ast_with, Symbol, SymbolTable};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{BitAnd, BitOr, BitXor, Sub};
 
-#[derive(Clone)]
-pub struct MySet(Vec<i32>);
+const SORTED_MAX: usize = 64;
+const BITSET_MAX: usize = 1 << 24;
+const SET_OPERATORS: [char; 7] = ['!', '&', '|', '^', '>', '=', '-'];
+const MULTISET_OPERATORS: [char; 6] = ['!', '&', '|', '^', '-', '+'];
 
-static UNIVERSE: Lazy<Mutex<MySet>> = Lazy::new(|| Mutex::new(MySet(vec![])));
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetError {
+    Parse(ParseError),
+    MissingSet {
+        offset: usize,
+        symbol: char,
+        count: usize,
+    },
+    DuplicateElement {
+        set: String,
+        position: usize,
+    },
+    InvalidMembership {
+        set: String,
+        position: usize,
+    },
+    UnknownElement {
+        set: String,
+        position: usize,
+    },
+}
+
+impl SetError {
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            SetError::Parse(e) => e.offset(),
+            SetError::MissingSet { offset, .. } => Some(*offset),
+            SetError::DuplicateElement { .. }
+            | SetError::InvalidMembership { .. }
+            | SetError::UnknownElement { .. } => None,
+        }
+    }
+}
+
+impl From<ParseError> for SetError {
+    fn from(e: ParseError) -> Self {
+        SetError::Parse(e)
+    }
+}
+
+impl fmt::Display for SetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SetError::Parse(e) => write!(f, "{}", e),
+            SetError::MissingSet {
+                offset,
+                symbol,
+                count,
+            } => write!(
+                f,
+                "no set given for variable '{}' at offset {} ({} sets provided)",
+                symbol, offset, count
+            ),
+            SetError::DuplicateElement { set, position } => write!(
+                f,
+                "set '{}' repeats an element at position {}",
+                set, position
+            ),
+            SetError::InvalidMembership { set, position } => write!(
+                f,
+                "set '{}' has a membership degree outside [0, 1] at position {}",
+                set, position
+            ),
+            SetError::UnknownElement { set, position } => write!(
+                f,
+                "set '{}' has an element outside the universe at position {}",
+                set, position
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SetError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    SortedVec,
+    HashSet,
+    BitSet,
+}
+
+impl Backend {
+    pub fn for_universe(size: usize) -> Backend {
+        if size <= SORTED_MAX {
+            Backend::SortedVec
+        } else if size <= BITSET_MAX {
+            Backend::BitSet
+        } else {
+            Backend::HashSet
+        }
+    }
+}
 
-impl BitAnd for MySet {
+#[derive(Clone, Debug)]
+enum Repr<T> {
+    Sorted(Vec<T>),
+    Hashed(HashSet<T>),
+    Bits(Vec<u64>),
+}
+
+#[derive(Clone, Debug)]
+pub struct MySet<T>(Repr<T>);
+
+impl<T> Default for MySet<T> {
+    fn default() -> Self {
+        MySet(Repr::Sorted(Vec::new()))
+    }
+}
+
+fn merge<T: Ord + Clone>(a: &[T], b: &[T], only_a: bool, both: bool, only_b: bool) -> Vec<T> {
+    let mut res = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => {
+                if only_a {
+                    res.push(a[i].clone());
+                }
+                i += 1;
+            }
+            Ordering::Greater => {
+                if only_b {
+                    res.push(b[j].clone());
+                }
+                j += 1;
+            }
+            Ordering::Equal => {
+                if both {
+                    res.push(a[i].clone());
+                }
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    if only_a {
+        res.extend_from_slice(&a[i..]);
+    }
+    if only_b {
+        res.extend_from_slice(&b[j..]);
+    }
+    res
+}
+
+fn zip_words(a: &[u64], b: &[u64], f: impl Fn(u64, u64) -> u64) -> Vec<u64> {
+    (0..a.len().max(b.len()))
+        .map(|i| {
+            f(
+                a.get(i).copied().unwrap_or(0),
+                b.get(i).copied().unwrap_or(0),
+            )
+        })
+        .collect()
+}
+
+impl<T: Ord + Hash + Clone> BitAnd for MySet<T> {
     type Output = Self;
 
     fn bitand(self, other: Self) -> Self::Output {
-        MySet(
-            self.0
-                .iter()
-                .filter_map(|n| if other.0.contains(n) { Some(*n) } else { None })
-                .collect::<Vec<i32>>(),
-        )
+        MySet(match (self.0, other.0) {
+            (Repr::Sorted(a), Repr::Sorted(b)) => Repr::Sorted(merge(&a, &b, false, true, false)),
+            (Repr::Hashed(a), Repr::Hashed(b)) => {
+                let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+                Repr::Hashed(small.into_iter().filter(|x| large.contains(x)).collect())
+            }
+            (Repr::Bits(a), Repr::Bits(b)) => Repr::Bits(zip_words(&a, &b, |x, y| x & y)),
+            _ => panic!("sets from different universes"),
+        })
     }
 }
 
-impl BitOr for MySet {
+impl<T: Ord + Hash + Clone> BitOr for MySet<T> {
     type Output = Self;
 
     fn bitor(self, other: Self) -> Self::Output {
-        MySet(
-            [
-                self.0.clone(),
-                other
-                    .0
-                    .iter()
-                    .filter_map(|n| if !self.0.contains(n) { Some(*n) } else { None })
-                    .collect(),
-            ]
-            .concat(),
-        )
+        MySet(match (self.0, other.0) {
+            (Repr::Sorted(a), Repr::Sorted(b)) => Repr::Sorted(merge(&a, &b, true, true, true)),
+            (Repr::Hashed(mut a), Repr::Hashed(b)) => {
+                a.extend(b);
+                Repr::Hashed(a)
+            }
+            (Repr::Bits(a), Repr::Bits(b)) => Repr::Bits(zip_words(&a, &b, |x, y| x | y)),
+            _ => panic!("sets from different universes"),
+        })
     }
 }
 
-impl Not for MySet {
+impl<T: Ord + Hash + Clone> BitXor for MySet<T> {
     type Output = Self;
 
-    fn not(self) -> Self::Output {
-        let universe = UNIVERSE.lock().unwrap();
-        MySet(
-            universe
-                .0
-                .iter()
-                .filter_map(|n| if !self.0.contains(n) { Some(*n) } else { None })
-                .collect(),
-        )
+    fn bitxor(self, other: Self) -> Self::Output {
+        MySet(match (self.0, other.0) {
+            (Repr::Sorted(a), Repr::Sorted(b)) => Repr::Sorted(merge(&a, &b, true, false, true)),
+            (Repr::Hashed(a), Repr::Hashed(b)) => {
+                Repr::Hashed(a.symmetric_difference(&b).cloned().collect())
+            }
+            (Repr::Bits(a), Repr::Bits(b)) => Repr::Bits(zip_words(&a, &b, |x, y| x ^ y)),
+            _ => panic!("sets from different universes"),
+        })
     }
 }
 
-fn build_universe(sets: &Vec<Vec<i32>>) -> MySet {
-    let mut all = Vec::new();
+impl<T: Ord + Hash + Clone> Sub for MySet<T> {
+    type Output = Self;
 
-    for s in sets {
-        for x in s {
-            if !all.contains(x) {
-                all.push(*x);
+    fn sub(self, other: Self) -> Self::Output {
+        MySet(match (self.0, other.0) {
+            (Repr::Sorted(a), Repr::Sorted(b)) => Repr::Sorted(merge(&a, &b, true, false, false)),
+            (Repr::Hashed(mut a), Repr::Hashed(b)) => {
+                a.retain(|x| !b.contains(x));
+                Repr::Hashed(a)
             }
-        }
+            (Repr::Bits(a), Repr::Bits(b)) => Repr::Bits(zip_words(&a, &b, |x, y| x & !y)),
+            _ => panic!("sets from different universes"),
+        })
     }
+}
 
-    MySet(all)
+#[derive(Clone, Debug)]
+pub struct Universe<T> {
+    elements: Vec<T>,
+    backend: Backend,
 }
 
-fn vec_tree(node: ASTNode<char>, sets: &Vec<Vec<i32>>) -> ASTNode<MySet> {
-    match node {
-        ASTNode::Value(c) => {
-            let idx = (c as u8 - b'A') as usize;
-            if idx >= sets.len() {
-                println!("Index out of bounds for sets");
-                exit(1);
-            }
-            ASTNode::Value(MySet(sets[idx].clone()))
+impl<T: Ord + Hash + Clone> Universe<T> {
+    pub fn new(elements: impl IntoIterator<Item = T>) -> Self {
+        let mut elements: Vec<T> = elements.into_iter().collect();
+        elements.sort();
+        elements.dedup();
+        let backend = Backend::for_universe(elements.len());
+        Universe { elements, backend }
+    }
+
+    pub fn with_backend(elements: impl IntoIterator<Item = T>, backend: Backend) -> Self {
+        Universe {
+            backend,
+            ..Universe::new(elements)
         }
-        ASTNode::Op {
-            operator,
-            left,
-            right,
-        } => {
-            let new_left = left.map(|l| Box::new(vec_tree(*l, sets)));
-            let new_right = Box::new(vec_tree(*right, sets));
-            ASTNode::Op {
-                operator,
-                left: new_left,
-                right: new_right,
+    }
+
+    pub fn elements(&self) -> &[T] {
+        &self.elements
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    fn set(&self, name: &str, elements: &[T]) -> Result<MySet<T>, SetError> {
+        let members = elements
+            .iter()
+            .enumerate()
+            .map(|(position, x)| {
+                self.elements
+                    .binary_search(x)
+                    .map_err(|_| SetError::UnknownElement {
+                        set: name.to_string(),
+                        position,
+                    })
+            })
+            .collect::<Result<Vec<usize>, SetError>>()?;
+        Ok(self.members(members))
+    }
+
+    fn members(&self, mut members: Vec<usize>) -> MySet<T> {
+        MySet(match self.backend {
+            Backend::SortedVec => {
+                members.sort();
+                members.dedup();
+                Repr::Sorted(
+                    members
+                        .into_iter()
+                        .map(|i| self.elements[i].clone())
+                        .collect(),
+                )
+            }
+            Backend::HashSet => Repr::Hashed(
+                members
+                    .into_iter()
+                    .map(|i| self.elements[i].clone())
+                    .collect(),
+            ),
+            Backend::BitSet => {
+                let mut words = vec![0; self.elements.len().div_ceil(64)];
+                for i in members {
+                    words[i / 64] |= 1 << (i % 64);
+                }
+                Repr::Bits(words)
+            }
+        })
+    }
+
+    fn empty(&self) -> MySet<T> {
+        self.members(Vec::new())
+    }
+
+    fn full(&self) -> MySet<T> {
+        self.members((0..self.elements.len()).collect())
+    }
+
+    fn complement(&self, set: MySet<T>) -> MySet<T> {
+        MySet(match set.0 {
+            Repr::Sorted(s) => Repr::Sorted(merge(&self.elements, &s, true, false, false)),
+            Repr::Hashed(s) => Repr::Hashed(
+                self.elements
+                    .iter()
+                    .filter(|x| !s.contains(*x))
+                    .cloned()
+                    .collect(),
+            ),
+            Repr::Bits(s) => {
+                let len = self.elements.len();
+                let mut words: Vec<u64> = s.iter().map(|w| !w).collect();
+                words.resize(len.div_ceil(64), !0);
+                if !len.is_multiple_of(64) {
+                    *words.last_mut().unwrap() &= (1 << (len % 64)) - 1;
+                }
+                Repr::Bits(words)
             }
+        })
+    }
+
+    fn elements_of(&self, set: MySet<T>) -> Vec<T> {
+        match set.0 {
+            Repr::Sorted(s) => s,
+            Repr::Hashed(s) => {
+                let mut res: Vec<T> = s.into_iter().collect();
+                res.sort();
+                res
+            }
+            Repr::Bits(s) => self
+                .elements
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| s.get(i / 64).is_some_and(|w| (w >> (i % 64)) & 1 == 1))
+                .map(|(_, x)| x.clone())
+                .collect(),
         }
     }
 }
 
-fn eval_node_vecs(node: ASTNode<MySet>) -> MySet {
-    match node {
-        ASTNode::Value(v) => v,
-        ASTNode::Op {
-            operator,
-            left,
-            right,
-        } => match operator {
-            '&' => eval_node_vecs(*left.unwrap()) & eval_node_vecs(*right),
-            '|' => eval_node_vecs(*left.unwrap()) | eval_node_vecs(*right),
-            '!' => !eval_node_vecs(*right),
-            _ => {
-                println!("Invalid operator in vec AST");
-                MySet(vec![])
-            }
-        },
+fn check_distinct<T: Hash + Eq>(set: &str, elements: &[T]) -> Result<(), SetError> {
+    let mut seen = HashSet::new();
+    match elements.iter().position(|x| !seen.insert(x)) {
+        Some(position) => Err(SetError::DuplicateElement {
+            set: set.to_string(),
+            position,
+        }),
+        None => Ok(()),
     }
 }
 
+fn set_index(c: char) -> usize {
+    (c as u8 - b'A') as usize
+}
+
+fn check_bound(formula: &str, count: usize) -> Result<(), SetError> {
+    match formula
+        .chars()
+        .enumerate()
+        .find(|(_, c)| c.is_ascii_uppercase() && set_index(*c) >= count)
+    {
+        Some((offset, symbol)) => Err(SetError::MissingSet {
+            offset,
+            symbol,
+            count,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn vec_tree<T: Ord + Hash + Clone>(
+    formula: &str,
+    node: &ASTNode<char>,
+    sets: &[Vec<T>],
+    universe: &Universe<T>,
+) -> Result<ASTNode<MySet<T>>, SetError> {
+    check_bound(formula, sets.len())?;
+    let members = sets
+        .iter()
+        .zip('A'..='Z')
+        .map(|(set, name)| {
+            check_distinct(&name.to_string(), set)?;
+            universe.set(&name.to_string(), set)
+        })
+        .collect::<Result<Vec<MySet<T>>, SetError>>()?;
+
+    Ok(node.map(&|c| match c {
+        '0' => universe.empty(),
+        '1' => universe.full(),
+        c => members[set_index(*c)].clone(),
+    }))
+}
+
+fn eval_node_vecs<T: Ord + Hash + Clone>(
+    node: &ASTNode<MySet<T>>,
+    universe: &Universe<T>,
+) -> MySet<T> {
+    node.fold(
+        |v| v.clone(),
+        |operator, left, right| match operator {
+            '&' => left.unwrap() & right,
+            '|' => left.unwrap() | right,
+            '^' => left.unwrap() ^ right,
+            '-' => left.unwrap() - right,
+            '>' => universe.complement(left.unwrap() - right),
+            '=' => universe.complement(left.unwrap() ^ right),
+            '!' => universe.complement(right),
+            _ => unreachable!("Invalid operator in vec AST"),
+        },
+    )
+}
+
+fn build_set_ast(formula: &str) -> Result<ASTNode<char>, ParseError> {
+    parse_rpn_with(formula, &SET_OPERATORS, |chars, i| {
+        let c = chars[i];
+        if c == '0' || c == '1' || c.is_ascii_uppercase() {
+            Some((c, i + 1))
+        } else {
+            None
+        }
+    })
+}
+
+pub fn eval_set_in<T: Ord + Hash + Clone>(
+    formula: &str,
+    sets: &[Vec<T>],
+    universe: &Universe<T>,
+) -> Result<Vec<T>, SetError> {
+    let tree = vec_tree(formula, &build_set_ast(formula)?, sets, universe)?;
+
+    Ok(universe.elements_of(eval_node_vecs(&tree, universe)))
+}
+
 #[allow(non_snake_case)]
-pub fn eval_set(formula: &str, sets: Vec<Vec<i32>>) -> Vec<i32> {
-    if formula.contains('1') || formula.contains('0') {
-        println!("Formula contains constants (0 or 1), cannot convert to NNF");
-        return vec![];
-    }
-    let mut tree = match build_ast(formula) {
-        Some(ast) => ast,
-        None => {
-            println!("Error in formula");
-            return vec![];
+pub fn eval_set<T: Ord + Hash + Clone>(
+    formula: &str,
+    sets: Vec<Vec<T>>,
+) -> Result<Vec<T>, SetError> {
+    let universe = Universe::new(sets.iter().flatten().cloned());
+    eval_set_in(formula, &sets, &universe)
+}
+
+pub fn eval_set_with<T: Ord + Hash + Clone>(
+    formula: &str,
+    sets: Vec<Vec<T>>,
+    backend: Backend,
+) -> Result<Vec<T>, SetError> {
+    let universe = Universe::with_backend(sets.iter().flatten().cloned(), backend);
+    eval_set_in(formula, &sets, &universe)
+}
+
+pub fn eval_named_set_in<T: Ord + Hash + Clone>(
+    formula: &str,
+    sets: &BTreeMap<String, Vec<T>>,
+    universe: &Universe<T>,
+) -> Result<Vec<T>, SetError> {
+    let mut table = SymbolTable::new();
+    let tree = build_named_ast_with(formula, &SET_OPERATORS, &mut table)?;
+    if let Some(name) = table.names().find(|name| !sets.contains_key(*name)) {
+        return Err(SetError::Parse(ParseError::UnboundVariable {
+            name: name.to_string(),
+        }));
+    }
+    let members = sets
+        .iter()
+        .map(|(name, set)| {
+            check_distinct(name, set)?;
+            Ok((name.clone(), universe.set(name, set)?))
+        })
+        .collect::<Result<BTreeMap<String, MySet<T>>, SetError>>()?;
+
+    let tree = tree.map(&|s| match s.constant() {
+        Some(true) => universe.full(),
+        Some(false) => universe.empty(),
+        None => members[&table.display(s)].clone(),
+    });
+
+    Ok(universe.elements_of(eval_node_vecs(&tree, universe)))
+}
+
+pub fn eval_named_set<T: Ord + Hash + Clone>(
+    formula: &str,
+    sets: &BTreeMap<String, Vec<T>>,
+) -> Result<Vec<T>, SetError> {
+    eval_named_set_in(
+        formula,
+        sets,
+        &Universe::new(sets.values().flatten().cloned()),
+    )
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TNorm {
+    #[default]
+    Min,
+    Product,
+    Lukasiewicz,
+}
+
+impl TNorm {
+    pub fn and(self, a: f64, b: f64) -> f64 {
+        match self {
+            TNorm::Min => a.min(b),
+            TNorm::Product => a * b,
+            TNorm::Lukasiewicz => (a + b - 1.0).max(0.0),
         }
-    };
+    }
 
-    let mut modified = true;
-    while modified {
-        modified = false;
-        tree_to_almost_nnf(&mut tree, &mut modified);
+    pub fn or(self, a: f64, b: f64) -> f64 {
+        match self {
+            TNorm::Min => a.max(b),
+            TNorm::Product => a + b - a * b,
+            TNorm::Lukasiewicz => (a + b).min(1.0),
+        }
     }
+}
 
-    let tree = vec_tree(tree, &sets);
+type BoundTree<'a, S> = (ASTNode<Symbol>, Vec<(String, &'a S)>);
 
-    {
-        let mut u = UNIVERSE.lock().unwrap();
-        *u = build_universe(&sets);
+fn positional_tree<'a, S>(
+    formula: &str,
+    operators: &[char],
+    sets: &'a [S],
+) -> Result<BoundTree<'a, S>, SetError> {
+    let tree = parse_rpn_with(formula, operators, |chars, i| match chars[i] {
+        '0' => Some((Symbol::False, i + 1)),
+        '1' => Some((Symbol::True, i + 1)),
+        c if c.is_ascii_uppercase() => Some((Symbol::Var(set_index(c)), i + 1)),
+        _ => None,
+    })?;
+    check_bound(formula, sets.len())?;
+
+    Ok((tree, ('A'..='Z').map(|c| c.to_string()).zip(sets).collect()))
+}
+
+fn named_tree<'a, S>(
+    formula: &str,
+    operators: &[char],
+    sets: &'a BTreeMap<String, S>,
+) -> Result<BoundTree<'a, S>, SetError> {
+    let mut table = SymbolTable::new();
+    let tree = build_named_ast_with(formula, operators, &mut table)?;
+    let positions = table
+        .names()
+        .map(|name| {
+            sets.keys()
+                .position(|key| key == name)
+                .ok_or_else(|| ParseError::UnboundVariable {
+                    name: name.to_string(),
+                })
+        })
+        .collect::<Result<Vec<usize>, ParseError>>()?;
+
+    Ok((
+        tree.map(&|s| match s {
+            Symbol::Var(id) => Symbol::Var(positions[*id]),
+            s => *s,
+        }),
+        sets.iter().map(|(name, set)| (name.clone(), set)).collect(),
+    ))
+}
+
+fn union_elements<'a, T: Ord + Clone + 'a>(sets: impl Iterator<Item = &'a T>) -> Vec<T> {
+    let mut elements: Vec<T> = sets.cloned().collect();
+    elements.sort();
+    elements.dedup();
+    elements
+}
+
+fn zip_with<A: Copy, R>(a: &[A], b: &[A], f: impl Fn(A, A) -> R) -> Vec<R> {
+    a.iter().zip(b).map(|(x, y)| f(*x, *y)).collect()
+}
+
+fn eval_multiset_tree<T: Ord + Clone>(
+    tree: &ASTNode<Symbol>,
+    sets: &[(String, &Vec<T>)],
+) -> Vec<(T, usize)> {
+    let elements = union_elements(sets.iter().flat_map(|(_, set)| set.iter()));
+    let counts: Vec<Vec<usize>> = sets
+        .iter()
+        .map(|(_, set)| {
+            let mut counts = vec![0; elements.len()];
+            for x in set.iter() {
+                counts[elements.binary_search(x).unwrap()] += 1;
+            }
+            counts
+        })
+        .collect();
+    let top: Vec<usize> = (0..elements.len())
+        .map(|i| counts.iter().map(|c| c[i]).max().unwrap_or(0))
+        .collect();
+
+    let res = tree.fold(
+        |s| match s {
+            Symbol::False => vec![0; elements.len()],
+            Symbol::True => top.clone(),
+            Symbol::Var(i) => counts[*i].clone(),
+        },
+        |operator, left, right| {
+            let Some(left) = left else {
+                return zip_with(&top, &right, usize::saturating_sub);
+            };
+            match operator {
+                '&' => zip_with(&left, &right, usize::min),
+                '|' => zip_with(&left, &right, usize::max),
+                '+' => zip_with(&left, &right, |a, b| a + b),
+                '-' => zip_with(&left, &right, usize::saturating_sub),
+                _ => zip_with(&left, &right, usize::abs_diff),
+            }
+        },
+    );
+
+    elements
+        .into_iter()
+        .zip(res)
+        .filter(|(_, n)| *n > 0)
+        .collect()
+}
+
+pub fn eval_multiset<T: Ord + Clone>(
+    formula: &str,
+    sets: Vec<Vec<T>>,
+) -> Result<Vec<(T, usize)>, SetError> {
+    let (tree, sets) = positional_tree(formula, &MULTISET_OPERATORS, &sets)?;
+    Ok(eval_multiset_tree(&tree, &sets))
+}
+
+pub fn eval_named_multiset<T: Ord + Clone>(
+    formula: &str,
+    sets: &BTreeMap<String, Vec<T>>,
+) -> Result<Vec<(T, usize)>, SetError> {
+    let (tree, sets) = named_tree(formula, &MULTISET_OPERATORS, sets)?;
+    Ok(eval_multiset_tree(&tree, &sets))
+}
+
+fn eval_fuzzy_tree<T: Ord + Hash + Clone>(
+    tree: &ASTNode<Symbol>,
+    sets: &[(String, &Vec<(T, f64)>)],
+    tnorm: TNorm,
+) -> Result<Vec<(T, f64)>, SetError> {
+    for (name, set) in sets {
+        let elements: Vec<&T> = set.iter().map(|(x, _)| x).collect();
+        check_distinct(name, &elements)?;
+        if let Some(position) = set.iter().position(|(_, d)| !(0.0..=1.0).contains(d)) {
+            return Err(SetError::InvalidMembership {
+                set: name.clone(),
+                position,
+            });
+        }
     }
-    eval_node_vecs(tree).0
+    let elements = union_elements(sets.iter().flat_map(|(_, set)| set.iter().map(|(x, _)| x)));
+    let degrees: Vec<Vec<f64>> = sets
+        .iter()
+        .map(|(_, set)| {
+            let mut degrees = vec![0.0; elements.len()];
+            for (x, d) in set.iter() {
+                degrees[elements.binary_search(x).unwrap()] = *d;
+            }
+            degrees
+        })
+        .collect();
+
+    let res = tree.fold(
+        |s| match s {
+            Symbol::False => vec![0.0; elements.len()],
+            Symbol::True => vec![1.0; elements.len()],
+            Symbol::Var(i) => degrees[*i].clone(),
+        },
+        |operator, left, right| {
+            let Some(left) = left else {
+                return right.iter().map(|a| 1.0 - a).collect();
+            };
+            zip_with(&left, &right, |a, b| match operator {
+                '&' => tnorm.and(a, b),
+                '|' => tnorm.or(a, b),
+                '-' => tnorm.and(a, 1.0 - b),
+                '^' => tnorm.or(tnorm.and(a, 1.0 - b), tnorm.and(1.0 - a, b)),
+                '>' => tnorm.or(1.0 - a, b),
+                _ => tnorm.and(tnorm.or(1.0 - a, b), tnorm.or(a, 1.0 - b)),
+            })
+        },
+    );
+
+    Ok(elements
+        .into_iter()
+        .zip(res)
+        .filter(|(_, d)| *d > 0.0)
+        .collect())
+}
+
+pub fn eval_fuzzy_set<T: Ord + Hash + Clone>(
+    formula: &str,
+    sets: Vec<Vec<(T, f64)>>,
+    tnorm: TNorm,
+) -> Result<Vec<(T, f64)>, SetError> {
+    let (tree, sets) = positional_tree(formula, &SET_OPERATORS, &sets)?;
+    eval_fuzzy_tree(&tree, &sets, tnorm)
+}
+
+pub fn eval_named_fuzzy_set<T: Ord + Hash + Clone>(
+    formula: &str,
+    sets: &BTreeMap<String, Vec<(T, f64)>>,
+    tnorm: TNorm,
+) -> Result<Vec<(T, f64)>, SetError> {
+    let (tree, sets) = named_tree(formula, &SET_OPERATORS, sets)?;
+    eval_fuzzy_tree(&tree, &sets, tnorm)
 }
@@ -0,0 +1,229 @@
+use crate::boolean_evaluation::fold_constants;
+use crate::boolean_evaluation::ASTNode;
+use crate::boolean_evaluation::Atom;
+use crate::boolean_evaluation::ParseError;
+use crate::conjunctive_normal_form::fold_right;
+use crate::negation_normal_form::tree_to_string;
+use crate::symbol_table::{build_named_ast, SymbolTable};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+const FINGERPRINT_DEPTH: usize = 4;
+
+fn flatten<V: Atom>(node: ASTNode<V>, operator: char, operands: &mut Vec<ASTNode<V>>) {
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        match &node {
+            ASTNode::Op {
+                operator: op,
+                left: Some(_),
+                ..
+            } if *op == operator => {
+                let (left, right) = node.into_children().unwrap();
+                stack.push(left.unwrap());
+                stack.push(right);
+            }
+            _ => operands.push(node),
+        }
+    }
+}
+
+fn terms<V: Atom>(node: &ASTNode<V>, operator: char) -> Vec<&ASTNode<V>> {
+    let mut res = Vec::new();
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        match node {
+            ASTNode::Op {
+                operator: op,
+                left: Some(left),
+                right,
+            } if *op == operator => {
+                stack.push(left);
+                stack.push(right);
+            }
+            _ => res.push(node),
+        }
+    }
+    res
+}
+
+fn fingerprint<V: Atom + Hash>(node: &ASTNode<V>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let mut stack = vec![(node, 0)];
+    while let Some((node, depth)) = stack.pop() {
+        match node {
+            ASTNode::Value(v) => v.hash(&mut hasher),
+            ASTNode::Op {
+                operator,
+                left,
+                right,
+            } => {
+                operator.hash(&mut hasher);
+                if depth < FINGERPRINT_DEPTH {
+                    stack.push((right, depth + 1));
+                    if let Some(l) = left {
+                        stack.push((l, depth + 1));
+                    }
+                }
+            }
+        }
+    }
+    hasher.finish()
+}
+
+fn subsumes<V: Atom>(a: &[&ASTNode<V>], b: &[&ASTNode<V>]) -> bool {
+    a.iter().all(|t| b.contains(t))
+}
+
+fn simplify_chain<V: Atom + Hash>(flat: Vec<ASTNode<V>>, operator: char) -> ASTNode<V> {
+    let (dual, absorbing) = if operator == '&' {
+        ('|', false)
+    } else {
+        ('&', true)
+    };
+
+    let mut operands: Vec<ASTNode<V>> = Vec::new();
+    let mut seen: HashMap<u64, Vec<usize>> = HashMap::new();
+    for operand in flat {
+        match &operand {
+            ASTNode::Value(v) if v.constant() == Some(absorbing) => return operand,
+            ASTNode::Value(v) if v.constant() == Some(!absorbing) => continue,
+            _ => {}
+        }
+        let bucket = seen.entry(fingerprint(&operand)).or_default();
+        if bucket.iter().all(|i| operands[*i] != operand) {
+            bucket.push(operands.len());
+            operands.push(operand);
+        }
+    }
+
+    let contains = |node: &ASTNode<V>| {
+        seen.get(&fingerprint(node))
+            .is_some_and(|bucket| bucket.iter().any(|i| operands[*i] == *node))
+    };
+    if operands.iter().any(|operand| match operand {
+        ASTNode::Op {
+            operator: '!',
+            right,
+            ..
+        } => contains(right),
+        _ => false,
+    }) {
+        return ASTNode::Value(V::from_constant(absorbing));
+    }
+
+    let terms: Vec<Vec<&ASTNode<V>>> = operands.iter().map(|o| terms(o, dual)).collect();
+    let compound: Vec<usize> = (0..operands.len())
+        .filter(|i| terms[*i].len() > 1)
+        .collect();
+    let kept: Vec<bool> = (0..operands.len())
+        .map(|i| {
+            let absorbs = |j: &usize| {
+                *j != i
+                    && subsumes(&terms[*j], &terms[i])
+                    && (*j < i || !subsumes(&terms[i], &terms[*j]))
+            };
+            if terms[i].len() > 1 {
+                !(0..operands.len()).any(|j| absorbs(&j))
+            } else {
+                !compound.iter().any(absorbs)
+            }
+        })
+        .collect();
+    let operands = operands
+        .into_iter()
+        .zip(kept)
+        .filter_map(|(operand, keep)| keep.then_some(operand))
+        .collect();
+
+    fold_right(operands, operator, V::from_constant(!absorbing))
+}
+
+fn simplify_not<V: Atom>(right: ASTNode<V>) -> ASTNode<V> {
+    match &right {
+        ASTNode::Op { operator: '!', .. } => right.into_children().unwrap().1,
+        ASTNode::Value(v) if v.constant().is_some() => {
+            ASTNode::Value(V::from_constant(v.constant() == Some(false)))
+        }
+        _ => ASTNode::Op {
+            operator: '!',
+            left: None,
+            right: Box::new(right),
+        },
+    }
+}
+
+enum Task<V: Default> {
+    Visit(ASTNode<V>),
+    Chain(char, usize),
+    Combine(char, bool),
+}
+
+fn simplify_node<V: Atom + Hash>(node: ASTNode<V>) -> ASTNode<V> {
+    let mut tasks = vec![Task::Visit(node)];
+    let mut results = Vec::new();
+    while let Some(task) = tasks.pop() {
+        match task {
+            Task::Visit(node) => {
+                let operator = match &node {
+                    ASTNode::Value(_) => {
+                        results.push(node);
+                        continue;
+                    }
+                    ASTNode::Op { operator, .. } => *operator,
+                };
+                if operator == '&' || operator == '|' {
+                    let mut flat = Vec::new();
+                    flatten(node, operator, &mut flat);
+                    tasks.push(Task::Chain(operator, flat.len()));
+                    tasks.extend(flat.into_iter().rev().map(Task::Visit));
+                    continue;
+                }
+                let (left, right) = node.into_children().unwrap();
+                tasks.push(Task::Combine(operator, left.is_some()));
+                tasks.push(Task::Visit(right));
+                if let Some(l) = left {
+                    tasks.push(Task::Visit(l));
+                }
+            }
+            Task::Chain(operator, count) => {
+                let flat = results.split_off(results.len() - count);
+                results.push(simplify_chain(flat, operator));
+            }
+            Task::Combine(operator, binary) => {
+                let right = results.pop().unwrap();
+                let node = if binary {
+                    let left = results.pop().unwrap();
+                    fold_constants(ASTNode::Op {
+                        operator,
+                        left: Some(Box::new(left)),
+                        right: Box::new(right),
+                    })
+                } else {
+                    simplify_not(right)
+                };
+                results.push(node);
+            }
+        }
+    }
+    results.pop().unwrap()
+}
+
+pub fn simplify_tree<V: Atom + Hash>(tree: ASTNode<V>) -> ASTNode<V> {
+    let mut tree = fold_constants(tree);
+    loop {
+        let next = simplify_node(tree.clone());
+        if next == tree {
+            return tree;
+        }
+        tree = next;
+    }
+}
+
+pub fn simplify(formula: &str) -> Result<String, ParseError> {
+    let mut table = SymbolTable::new();
+    let tree = build_named_ast(formula, &mut table)?;
+
+    Ok(tree_to_string(&table.resolve(&simplify_tree(tree))))
+}
@@ -0,0 +1,139 @@
+use crate::boolean_evaluation::{parse_rpn_with, ASTNode, Atom, ParseError, OPERATORS};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Symbol {
+    #[default]
+    False,
+    True,
+    Var(usize),
+}
+
+impl Atom for Symbol {
+    fn constant(&self) -> Option<bool> {
+        match self {
+            Symbol::False => Some(false),
+            Symbol::True => Some(true),
+            Symbol::Var(_) => None,
+        }
+    }
+
+    fn from_constant(value: bool) -> Self {
+        if value {
+            Symbol::True
+        } else {
+            Symbol::False
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SymbolTable {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, name: &str) -> usize {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    pub fn fresh(&mut self, prefix: &str) -> usize {
+        let mut n = self.names.len();
+        while self.ids.contains_key(&format!("{}{}", prefix, n)) {
+            n += 1;
+        }
+        self.intern(&format!("{}{}", prefix, n))
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|n| n.as_str())
+    }
+
+    pub fn display(&self, symbol: &Symbol) -> String {
+        match symbol {
+            Symbol::False => "0".to_string(),
+            Symbol::True => "1".to_string(),
+            Symbol::Var(id) => self.names[*id].clone(),
+        }
+    }
+
+    pub fn resolve(&self, tree: &ASTNode<Symbol>) -> ASTNode<String> {
+        tree.map(&|s| self.display(s))
+    }
+}
+
+pub fn scan_name(chars: &[char], start: usize) -> usize {
+    match chars.get(start) {
+        Some(c) if c.is_ascii_uppercase() => start + 1,
+        Some(c) if c.is_ascii_lowercase() || *c == '_' => {
+            let mut end = start + 1;
+            while end < chars.len()
+                && (chars[end].is_ascii_alphanumeric() || "_[].".contains(chars[end]))
+            {
+                end += 1;
+            }
+            end
+        }
+        _ => start,
+    }
+}
+
+pub fn build_named_ast(
+    formula: &str,
+    table: &mut SymbolTable,
+) -> Result<ASTNode<Symbol>, ParseError> {
+    build_named_ast_with(formula, &OPERATORS, table)
+}
+
+pub fn build_named_ast_with(
+    formula: &str,
+    operators: &[char],
+    table: &mut SymbolTable,
+) -> Result<ASTNode<Symbol>, ParseError> {
+    parse_rpn_with(formula, operators, |chars, i| match chars[i] {
+        '0' => Some((Symbol::False, i + 1)),
+        '1' => Some((Symbol::True, i + 1)),
+        _ => {
+            let end = scan_name(chars, i);
+            if end == i {
+                return None;
+            }
+            let name: String = chars[i..end].iter().collect();
+            Some((Symbol::Var(table.intern(&name)), end))
+        }
+    })
+}
+
+pub fn join_rpn(tokens: &[String]) -> String {
+    let is_operand = |t: &str| t.chars().count() > 1 || !"!&|^>=".contains(t);
+    let is_name = |t: &str| t.starts_with(|c: char| c.is_ascii_lowercase() || c == '_');
+    let mut res = String::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0
+            && is_operand(&tokens[i - 1])
+            && is_operand(token)
+            && (tokens[i - 1].chars().count() > 1
+                || token.chars().count() > 1
+                || is_name(&tokens[i - 1]))
+        {
+            res.push(' ');
+        }
+        res.push_str(token);
+    }
+    res
+}
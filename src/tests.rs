@@ -1,14 +1,28 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::adder;
+    use crate::bdd;
     use crate::boolean_evaluation;
+    use crate::bytecode;
+    use crate::cdcl;
+    use crate::cli;
     use crate::conjunctive_normal_form;
+    use crate::dag;
+    use crate::dimacs;
+    use crate::disjunctive_normal_form;
+    use crate::equivalence;
     use crate::gray_code;
+    use crate::infix;
+    use crate::minimize;
     use crate::multiplier;
     use crate::negation_normal_form;
     use crate::powerset;
+    use crate::repl;
     use crate::sat;
     use crate::set_evaluation;
+    use crate::simplify;
+    use crate::symbol_table;
     use crate::truth_table;
 
     #[test]
@@ -56,17 +70,190 @@ mod tests {
 
     #[test]
     fn test_boolean_evaluation_build_ast() {
-        assert!(boolean_evaluation::build_ast("10&").is_some());
-        assert!(boolean_evaluation::build_ast("AB&").is_some());
-        assert!(boolean_evaluation::build_ast("invalid").is_none());
+        assert!(boolean_evaluation::build_ast("10&").is_ok());
+        assert!(boolean_evaluation::build_ast("AB&").is_ok());
+        assert!(boolean_evaluation::build_ast("invalid").is_err());
+    }
+
+    #[test]
+    fn test_boolean_evaluation_parse_errors() {
+        use boolean_evaluation::ParseError;
+        assert_eq!(
+            boolean_evaluation::build_ast("AB&a"),
+            Err(ParseError::UnknownSymbol {
+                offset: 3,
+                symbol: 'a'
+            })
+        );
+        assert_eq!(
+            boolean_evaluation::build_ast("A&"),
+            Err(ParseError::StackUnderflow {
+                offset: 1,
+                symbol: '&'
+            })
+        );
+        assert_eq!(
+            boolean_evaluation::build_ast("AB!"),
+            Err(ParseError::LeftoverOperands {
+                offset: 2,
+                symbol: '!',
+                count: 2
+            })
+        );
+        assert_eq!(
+            boolean_evaluation::build_ast(""),
+            Err(ParseError::EmptyFormula)
+        );
+        assert_eq!(
+            boolean_evaluation::eval_formula("1A&"),
+            Err(ParseError::UnexpectedVariable {
+                offset: 1,
+                symbol: 'A'
+            })
+        );
     }
 
     #[test]
     fn test_boolean_evaluation_eval_formula() {
-        assert_eq!(boolean_evaluation::eval_formula("10&"), false);
-        assert_eq!(boolean_evaluation::eval_formula("10|"), true);
-        assert_eq!(boolean_evaluation::eval_formula("11>"), true);
-        assert_eq!(boolean_evaluation::eval_formula("10="), false);
+        assert_eq!(boolean_evaluation::eval_formula("10&"), Ok(false));
+        assert_eq!(boolean_evaluation::eval_formula("10|"), Ok(true));
+        assert_eq!(boolean_evaluation::eval_formula("11>"), Ok(true));
+        assert_eq!(boolean_evaluation::eval_formula("10="), Ok(false));
+    }
+
+    #[test]
+    fn test_infix_parse_infix() {
+        assert_eq!(
+            infix::infix_to_rpn("(A & B) | !C"),
+            Ok("AB&C!|".to_string())
+        );
+        assert_eq!(infix::infix_to_rpn("A | B & C"), Ok("ABC&|".to_string()));
+        assert_eq!(
+            infix::infix_to_rpn("A ^ B | C ^ D"),
+            Ok("AB^CD^|".to_string())
+        );
+        assert_eq!(infix::infix_to_rpn("A -> B -> C"), Ok("ABC>>".to_string()));
+        assert_eq!(infix::infix_to_rpn("A -> B <-> C"), Ok("AB>C=".to_string()));
+        assert_eq!(infix::infix_to_rpn("¬A ∧ ⊤ → B"), Ok("A!1&B>".to_string()));
+        assert_eq!(
+            infix::parse_infix("(A & B"),
+            Err(boolean_evaluation::ParseError::UnclosedParenthesis { offset: 0 })
+        );
+        assert_eq!(
+            infix::parse_infix("A B"),
+            Err(boolean_evaluation::ParseError::UnexpectedSymbol {
+                offset: 2,
+                symbol: 'B'
+            })
+        );
+        assert_eq!(
+            infix::parse_infix("A &"),
+            Err(boolean_evaluation::ParseError::UnexpectedEnd { offset: 3 })
+        );
+    }
+
+    #[test]
+    fn test_infix_round_trip() {
+        for rpn in [
+            "AB&C!|", "ABC>>", "AB>C>", "AB|C&!", "AB=C=", "ABC==", "A!!B^",
+        ] {
+            let infix = infix::rpn_to_infix(rpn).unwrap();
+            assert_eq!(infix::infix_to_rpn(&infix), Ok(rpn.to_string()));
+        }
+        assert_eq!(
+            infix::rpn_to_infix("AB>C>"),
+            Ok("(A -> B) -> C".to_string())
+        );
+        assert_eq!(
+            infix::tree_to_unicode(&boolean_evaluation::build_ast("AB|!C0&=").unwrap()),
+            "¬(A ∨ B) ↔ C ∧ ⊥"
+        );
+        for rpn in ["AB-", "AB-C-", "ABC--", "AB-C&", "ABC-&", "AB-C>"] {
+            let infix = infix::rpn_to_infix(rpn).unwrap();
+            assert_eq!(infix::infix_to_rpn(&infix), Ok(rpn.to_string()));
+            let unicode = infix::rpn_to_unicode(rpn).unwrap();
+            assert_eq!(infix::infix_to_rpn(&unicode), Ok(rpn.to_string()));
+        }
+        assert_eq!(infix::rpn_to_infix("ABC--"), Ok("A - (B - C)".to_string()));
+        assert_eq!(infix::rpn_to_unicode("AB-C|"), Ok("A ∖ B ∨ C".to_string()));
+        assert_eq!(infix::infix_to_rpn("A->B-C"), Ok("ABC->".to_string()));
+        assert_eq!(
+            run_cli(&["sets", "--format", "infix", "A-B", "1,2", "2,3"], ""),
+            (0, "[1]\n".to_string(), String::new())
+        );
+        assert_eq!(
+            run_cli(&["sets", "--format", "unicode", "A ∖ ¬B", "1,2", "2,3"], ""),
+            (0, "[2]\n".to_string(), String::new())
+        );
+    }
+
+    #[test]
+    fn test_symbol_table_build_named_ast() {
+        use symbol_table::Symbol;
+        let mut table = symbol_table::SymbolTable::new();
+        let tree = symbol_table::build_named_ast("req_valid x[12]&req_valid|", &mut table).unwrap();
+        assert_eq!(
+            table.names().collect::<Vec<_>>(),
+            vec!["req_valid", "x[12]"]
+        );
+        assert_eq!(table.intern("x[12]"), 1);
+        assert_eq!(
+            tree,
+            boolean_evaluation::ASTNode::Op {
+                operator: '|',
+                left: Some(Box::new(boolean_evaluation::ASTNode::Op {
+                    operator: '&',
+                    left: Some(Box::new(boolean_evaluation::ASTNode::Value(Symbol::Var(0)))),
+                    right: Box::new(boolean_evaluation::ASTNode::Value(Symbol::Var(1))),
+                })),
+                right: Box::new(boolean_evaluation::ASTNode::Value(Symbol::Var(0))),
+            }
+        );
+        assert_eq!(
+            negation_normal_form::tree_to_string(&table.resolve(&tree)),
+            "req_valid x[12] req_valid&|"
+        );
+        assert_eq!(
+            infix::infix_to_rpn("a & b | x1 & A"),
+            Ok("a b&x1 A&|".to_string())
+        );
+        assert!(symbol_table::build_named_ast("AB1&|", &mut table).is_ok());
+        assert!(symbol_table::build_named_ast("a b", &mut table).is_err());
+    }
+
+    #[test]
+    fn test_boolean_evaluation_eval_formula_with() {
+        use std::collections::BTreeMap;
+        let assignment = BTreeMap::from([("req".to_string(), true), ("ack".to_string(), false)]);
+        assert_eq!(
+            boolean_evaluation::eval_formula_with("req ack>", &assignment),
+            Ok(false)
+        );
+        assert_eq!(
+            boolean_evaluation::eval_formula_with("req ack|1&", &assignment),
+            Ok(true)
+        );
+        assert_eq!(
+            boolean_evaluation::eval_formula_with("req grant&", &assignment),
+            Err(boolean_evaluation::ParseError::UnboundVariable {
+                name: "grant".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_named_variables() {
+        assert_eq!(
+            negation_normal_form::negation_normal_form("req_valid x[12]&!"),
+            Ok("x[12]!req_valid!|".to_string())
+        );
+        assert_eq!(
+            conjunctive_normal_form::conjunctive_normal_form("a1 b2|!"),
+            Ok("b2!a1!&".to_string())
+        );
+        assert_eq!(sat::sat("sig sig!&"), Ok(false));
+        assert_eq!(sat::sat("sig_a sig_b!&"), Ok(true));
+        assert!(truth_table::print_truth_table("req_valid ack>").is_ok());
     }
 
     #[test]
@@ -76,14 +263,23 @@ mod tests {
         dict.insert('A', true);
         dict.insert('B', false);
         assert_eq!(truth_table::substitute_vars("AB&", &dict), "10&");
+        let mut dict = BTreeMap::new();
+        dict.insert("x1".to_string(), true);
+        dict.insert("x".to_string(), false);
+        assert_eq!(truth_table::substitute_vars("x1 x&", &dict), "1 0&");
     }
 
     #[test]
     fn test_truth_table_create_dict() {
         let dict = truth_table::create_dict("AB&C");
-        assert!(dict.contains_key(&'A'));
-        assert!(dict.contains_key(&'B'));
-        assert!(dict.contains_key(&'C'));
+        assert!(dict.contains_key("A"));
+        assert!(dict.contains_key("B"));
+        assert!(dict.contains_key("C"));
+        let dict = truth_table::create_dict("req_valid x[12]&A|");
+        assert_eq!(
+            dict.keys().collect::<Vec<_>>(),
+            vec!["A", "req_valid", "x[12]"]
+        );
     }
 
     #[test]
@@ -93,39 +289,1255 @@ mod tests {
         dict.insert('A', false);
         dict.insert('B', false);
         truth_table::update_dict(&mut dict, 1);
-        assert_eq!(*dict.get(&'B').unwrap(), true);
+        assert!(*dict.get(&'B').unwrap());
     }
 
     #[test]
     fn test_truth_table_print_truth_table() {
         // Just call to ensure no panic
-        truth_table::print_truth_table("AB&C");
+        assert!(truth_table::print_truth_table("AB&C|").is_ok());
+        assert!(truth_table::print_truth_table("AB&C").is_err());
     }
 
     #[test]
     fn test_sat() {
-        assert_eq!(sat::sat("AB|"), true);
-        assert_eq!(sat::sat("AB&"), true);
-        assert_eq!(sat::sat("AA!&"), false);
+        assert_eq!(sat::sat("AB|"), Ok(true));
+        assert_eq!(sat::sat("AB&"), Ok(true));
+        assert_eq!(sat::sat("AA!&"), Ok(false));
+    }
+
+    #[test]
+    fn test_sat_assignment() {
+        use std::collections::BTreeMap;
+        assert_eq!(
+            sat::sat_assignment("AB!&"),
+            Ok(Some(BTreeMap::from([
+                ("A".to_string(), true),
+                ("B".to_string(), false)
+            ])))
+        );
+        assert_eq!(sat::sat_assignment("AA!&"), Ok(None));
+        let witness = sat::sat_assignment("req ack^ ack grant|&")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            boolean_evaluation::eval_formula_with("req ack^ ack grant|&", &witness),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn test_sat_models() {
+        let models: Vec<_> = sat::models("AB|").unwrap().collect();
+        assert_eq!(models.len(), 3);
+        for model in models.iter() {
+            assert_eq!(
+                boolean_evaluation::eval_formula_with("AB|", model),
+                Ok(true)
+            );
+        }
+        assert_eq!(sat::models("AA!&").unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_sat_count_models() {
+        assert_eq!(sat::count_models("AB|"), Ok(Some(3)));
+        assert_eq!(sat::count_models("AB^C^"), Ok(Some(4)));
+        assert_eq!(sat::count_models("AA!|B|"), Ok(Some(4)));
+        assert_eq!(sat::count_models("AA!&"), Ok(Some(0)));
+        let or = |n: usize| (1..n).fold("x0".to_string(), |acc, i| format!("{} x{}|", acc, i));
+        assert_eq!(sat::count_models(&or(17)), Ok(Some((1 << 17) - 1)));
+        assert_eq!(sat::count_models(&or(100)), Ok(Some((1 << 100) - 1)));
+        assert_eq!(
+            sat::count_models(&format!("{} y 0&|", or(20))),
+            Ok(Some(((1 << 20) - 1) * 2))
+        );
+        assert_eq!(sat::count_models(&or(130)), Ok(None));
+        assert_eq!(
+            run_cli(&["sat", "--count", "--json", &or(17)], "").1,
+            format!("{{\"formula\": \"{}\", \"count\": 131071}}\n", or(17))
+        );
+    }
+
+    #[test]
+    fn test_sat_many_variables() {
+        let mut formula = String::from("x0");
+        for i in 1..60 {
+            formula.push_str(&format!(" x{}&", i));
+        }
+        assert_eq!(sat::sat(&formula), Ok(true));
+        formula.push_str(" x0!&");
+        assert_eq!(sat::sat(&formula), Ok(false));
+    }
+
+    #[test]
+    fn test_equivalence_equivalent() {
+        use equivalence::Check;
+        use std::collections::BTreeMap;
+        for formula in ["AB&!", "AB|C&!", "AB=", "AB>C^", "ABC||D&!"] {
+            let nnf = negation_normal_form::negation_normal_form(formula).unwrap();
+            let cnf = conjunctive_normal_form::conjunctive_normal_form(formula).unwrap();
+            assert_eq!(equivalence::equivalent(formula, &nnf), Ok(Check::Holds));
+            assert_eq!(equivalence::equivalent(formula, &cnf), Ok(Check::Holds));
+        }
+        assert_eq!(
+            equivalence::equivalent("AB>", "BA>"),
+            Ok(Check::Counterexample(BTreeMap::from([
+                ("A".to_string(), false),
+                ("B".to_string(), true)
+            ])))
+        );
+        assert!(equivalence::equivalent("AB&", "AB").is_err());
+    }
+
+    #[test]
+    fn test_equivalence_entails() {
+        use equivalence::Check;
+        assert_eq!(equivalence::entails("AB&", "A"), Ok(Check::Holds));
+        assert_eq!(equivalence::entails("A", "AB|"), Ok(Check::Holds));
+        let Ok(Check::Counterexample(model)) = equivalence::entails("AB|", "A") else {
+            panic!("A | B does not entail A");
+        };
+        assert!(!model["A"]);
+        assert!(model["B"]);
+    }
+
+    #[test]
+    fn test_equivalence_tautology_contradiction() {
+        use equivalence::Check;
+        use std::collections::BTreeMap;
+        assert!(equivalence::is_tautology("AA!|").unwrap().holds());
+        assert!(equivalence::is_tautology("AB>A>A>").unwrap().holds());
+        assert_eq!(
+            equivalence::is_tautology("AB|"),
+            Ok(Check::Counterexample(BTreeMap::from([
+                ("A".to_string(), false),
+                ("B".to_string(), false)
+            ])))
+        );
+        assert!(equivalence::is_contradiction("AA!&").unwrap().holds());
+        let Ok(Check::Counterexample(model)) = equivalence::is_contradiction("AB!&") else {
+            panic!("A & !B is satisfiable");
+        };
+        assert_eq!(
+            model,
+            BTreeMap::from([("A".to_string(), true), ("B".to_string(), false)])
+        );
+    }
+
+    #[test]
+    fn test_bdd_apply_and_count() {
+        let mut manager = bdd::BddManager::new();
+        let a = manager.from_ast(&boolean_evaluation::build_ast("AB&!").unwrap());
+        let b = manager.from_ast(&boolean_evaluation::build_ast("A!B!|").unwrap());
+        assert_eq!(a, b);
+        let c = manager.from_ast(&boolean_evaluation::build_ast("AB=C>").unwrap());
+        assert_eq!(manager.model_count(a), Some(6));
+        assert_eq!(manager.model_count(c), Some(6));
+        for op in ['&', '|', '^', '>', '='] {
+            let f = manager.apply(op, a, c);
+            for i in 0..8 {
+                let value = |v: usize| i >> v & 1 == 1;
+                let expected = match op {
+                    '&' => manager.eval(a, &value) & manager.eval(c, &value),
+                    '|' => manager.eval(a, &value) | manager.eval(c, &value),
+                    '^' => manager.eval(a, &value) ^ manager.eval(c, &value),
+                    '>' => !manager.eval(a, &value) | manager.eval(c, &value),
+                    _ => manager.eval(a, &value) == manager.eval(c, &value),
+                };
+                assert_eq!(manager.eval(f, &value), expected);
+            }
+        }
+        let tautology = manager.from_ast(&boolean_evaluation::build_ast("AA!|").unwrap());
+        assert_eq!(tautology, bdd::Bdd::TRUE);
+    }
+
+    #[test]
+    fn test_bdd_restrict_and_quantify() {
+        let mut manager = bdd::BddManager::new();
+        let f = manager.from_ast(&boolean_evaluation::build_ast("AB&C|").unwrap());
+        let c = manager.var(2);
+        assert_eq!(manager.restrict(f, 0, false), c);
+        let b_or_c = manager.from_ast(&boolean_evaluation::build_ast("BC|").unwrap());
+        assert_eq!(manager.exists(f, &[0]), b_or_c);
+        assert_eq!(manager.forall(f, &[0]), c);
+        assert_eq!(manager.exists(f, &[0, 1, 2]), bdd::Bdd::TRUE);
+        assert_eq!(manager.forall(f, &[0, 1, 2]), bdd::Bdd::FALSE);
+    }
+
+    #[test]
+    fn test_bdd_variable_order() {
+        let formula = boolean_evaluation::build_ast("AB&CD&|EF&|").unwrap();
+        let mut interleaved = bdd::BddManager::with_order(&[0, 1, 2, 3, 4, 5]);
+        let mut separated = bdd::BddManager::with_order(&[0, 2, 4, 1, 3, 5]);
+        let f = interleaved.from_ast(&formula);
+        let g = separated.from_ast(&formula);
+        assert_eq!(interleaved.size(f), 6);
+        assert_eq!(separated.size(g), 14);
+        assert_eq!(interleaved.model_count(f), separated.model_count(g));
+        assert_eq!(separated.order(), &[0, 2, 4, 1, 3, 5]);
+        assert_eq!(bdd::bdd_equivalent("a b>", "a! b|"), Ok(true));
+        assert_eq!(bdd::bdd_equivalent("a b>", "b a>"), Ok(false));
+    }
+
+    #[test]
+    fn test_bdd_deep_and_wide() {
+        let mut manager = bdd::BddManager::with_order(&(0..128).collect::<Vec<_>>());
+        let x = manager.var(0);
+        let y = manager.var(1);
+        let x_or_y = manager.apply('|', x, y);
+        assert_eq!(manager.model_count(x), Some(1 << 127));
+        assert_eq!(manager.model_count(x_or_y), Some(3 << 126));
+        assert_eq!(manager.model_count(bdd::Bdd::TRUE), None);
+
+        let n = 200_000;
+        let formula = (0..n).map(|i| format!("x{} ", i)).collect::<String>() + &"&".repeat(n - 1);
+        let tree =
+            symbol_table::build_named_ast(&formula, &mut symbol_table::SymbolTable::new()).unwrap();
+        let mut manager = bdd::BddManager::with_order(&(0..n).collect::<Vec<_>>());
+        let f = manager.from_named_ast(&tree);
+        assert_eq!(manager.size(f), n);
+        assert_eq!(manager.model_count(f), Some(1));
+        let g = manager.not(f);
+        assert_eq!(manager.model_count(g), None);
+        assert_eq!(manager.apply('|', f, g), bdd::Bdd::TRUE);
+        let h = manager.restrict(f, n - 1, true);
+        assert_eq!(manager.size(h), n - 1);
+    }
+
+    #[test]
+    fn test_cdcl_pigeonhole() {
+        let (pigeons, holes) = (6, 5);
+        let var = |p: usize, h: usize| p * holes + h;
+        let mut solver = cdcl::Solver::new(pigeons * holes);
+        for p in 0..pigeons {
+            let clause: Vec<_> = (0..holes)
+                .map(|h| cdcl::Lit::new(var(p, h), true))
+                .collect();
+            solver.add_clause(&clause);
+        }
+        for h in 0..holes {
+            for p in 0..pigeons {
+                for q in (p + 1)..pigeons {
+                    solver.add_clause(&[
+                        cdcl::Lit::new(var(p, h), false),
+                        cdcl::Lit::new(var(q, h), false),
+                    ]);
+                }
+            }
+        }
+        assert_eq!(solver.solve(), None);
+    }
+
+    #[test]
+    fn test_cdcl_random_3sat() {
+        let mut seed: u64 = 42;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize
+        };
+        for _ in 0..50 {
+            let vars = 12;
+            let clauses: Vec<Vec<cdcl::Lit>> = (0..50)
+                .map(|_| {
+                    (0..3)
+                        .map(|_| cdcl::Lit::new(next() % vars, next() % 2 == 0))
+                        .collect()
+                })
+                .collect();
+            let satisfies = |model: &[bool]| {
+                clauses
+                    .iter()
+                    .all(|c| c.iter().any(|l| model[l.var()] == l.is_positive()))
+            };
+            let brute_force = (0..1u32 << vars).any(|bits| {
+                let model: Vec<bool> = (0..vars).map(|v| bits >> v & 1 == 1).collect();
+                satisfies(&model)
+            });
+
+            let mut solver = cdcl::Solver::new(vars);
+            for clause in clauses.iter() {
+                solver.add_clause(clause);
+            }
+            match solver.solve() {
+                Some(model) => assert!(brute_force && satisfies(&model)),
+                None => assert!(!brute_force),
+            }
+        }
+    }
+
+    #[test]
+    fn test_minimize_minimal_dnf_cnf() {
+        assert_eq!(minimize::minimal_dnf("AB&A!B&|"), Ok("B".to_string()));
+        assert_eq!(minimize::minimal_dnf("AB|C&"), Ok("AC&BC&|".to_string()));
+        assert_eq!(minimize::minimal_cnf("AB&C|"), Ok("AC|BC|&".to_string()));
+        assert_eq!(minimize::minimal_dnf("AA!&"), Ok("0".to_string()));
+        assert_eq!(minimize::minimal_cnf("AA!|"), Ok("1".to_string()));
+        for formula in ["AB^C^", "AB>C=", "ABC||D&!", "a b & c | d ^"] {
+            let dnf = minimize::minimal_dnf(formula).unwrap();
+            let cnf = minimize::minimal_cnf(formula).unwrap();
+            assert!(equivalence::equivalent(formula, &dnf).unwrap().holds());
+            assert!(equivalence::equivalent(formula, &cnf).unwrap().holds());
+        }
+        assert_eq!(
+            minimize::minimize("AB&", Some("A!B&"), minimize::Form::Dnf),
+            Ok("B".to_string())
+        );
+    }
+
+    #[test]
+    fn test_minimize_quine_mccluskey_espresso() {
+        let check = |vars: usize, on: &[u64], dc: &[u64], cover: &[minimize::Cube]| {
+            for m in 0..1u64 << vars {
+                let covered = cover.iter().any(|c| c.covers(m));
+                if on.contains(&m) {
+                    assert!(covered);
+                } else if !dc.contains(&m) {
+                    assert!(!covered);
+                }
+            }
+        };
+        let (on, dc) = ([4, 8, 10, 11, 12, 15], [9, 14]);
+        let exact = minimize::quine_mccluskey(4, &on, &dc);
+        check(4, &on, &dc, &exact);
+        assert_eq!(exact.len(), 3);
+        assert_eq!(exact.iter().map(|c| c.literals()).sum::<u32>(), 7);
+
+        let mut seed: u64 = 7;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            seed >> 33
+        };
+        for vars in [5, 6, 10] {
+            let mut on = Vec::new();
+            let mut dc = Vec::new();
+            for m in 0..1u64 << vars {
+                match next() % 4 {
+                    0 | 1 => on.push(m),
+                    2 if vars < 10 => dc.push(m),
+                    _ => {}
+                }
+            }
+            let heuristic = minimize::espresso(vars, &on, &dc);
+            check(vars, &on, &dc, &heuristic);
+            if vars < 10 {
+                let exact = minimize::quine_mccluskey(vars, &on, &dc);
+                check(vars, &on, &dc, &exact);
+                assert!(exact.len() <= heuristic.len());
+            }
+        }
     }
 
     #[test]
     fn test_negation_normal_form() {
-        assert_eq!(negation_normal_form::negation_normal_form("AB&!"), "B!A!|");
+        assert_eq!(
+            negation_normal_form::negation_normal_form("AB&!"),
+            Ok("B!A!|".to_string())
+        );
+        for formula in ["AB=C^!", "AB>!C|D=!", "AB^!!C>D&!"] {
+            let mut tree =
+                symbol_table::build_named_ast(formula, &mut symbol_table::SymbolTable::new())
+                    .unwrap();
+            let single_pass = negation_normal_form::nnf_tree(tree.clone());
+            let mut modified = true;
+            while modified {
+                modified = false;
+                negation_normal_form::tree_to_almost_nnf(&mut tree, &mut modified);
+            }
+            assert_eq!(single_pass, tree);
+        }
     }
 
     #[test]
     fn test_conjunctive_normal_form() {
         assert_eq!(
             conjunctive_normal_form::conjunctive_normal_form("AB|!"),
-            "B!A!&"
+            Ok("B!A!&".to_string())
+        );
+    }
+
+    #[test]
+    fn test_conjunctive_normal_form_with() {
+        use crate::tseitin::CnfMode;
+        assert_eq!(
+            conjunctive_normal_form::conjunctive_normal_form_with("AB|!", CnfMode::Equivalent),
+            Ok("B!A!&".to_string())
+        );
+        assert_eq!(
+            conjunctive_normal_form::conjunctive_normal_form_with("AB&", CnfMode::Tseitin),
+            Ok("_t2!A|_t2!B|_t2 A!B!||_t2&&&".to_string())
+        );
+        assert_eq!(
+            conjunctive_normal_form::conjunctive_normal_form_with(
+                "AB&",
+                CnfMode::PlaistedGreenbaum
+            ),
+            Ok("_t2!A|_t2!B|_t2&&".to_string())
+        );
+
+        let mut xor_chain = String::from("x0");
+        for i in 1..40 {
+            xor_chain.push_str(&format!(" x{}^", i));
+        }
+        let cnf =
+            conjunctive_normal_form::conjunctive_normal_form_with(&xor_chain, CnfMode::Tseitin)
+                .unwrap();
+        assert!(cnf.matches('&').count() < 4 * 40);
+    }
+
+    #[test]
+    fn test_disjunctive_normal_form() {
+        assert_eq!(
+            disjunctive_normal_form::disjunctive_normal_form("AB|C&"),
+            Ok("BC&AC&|".to_string())
+        );
+        assert_eq!(
+            disjunctive_normal_form::disjunctive_normal_form("AB|!"),
+            Ok("B!A!&".to_string())
+        );
+        for formula in ["AB&C|D&", "AB|CD|&", "AB>C=", "AB^!"] {
+            let dnf = disjunctive_normal_form::disjunctive_normal_form(formula).unwrap();
+            assert!(equivalence::equivalent(formula, &dnf).unwrap().holds());
+            assert!(!dnf.contains(['>', '=', '^']));
+        }
+        assert_eq!(
+            disjunctive_normal_form::disjunctive_normal_form("A1&B0|&"),
+            Ok("BA&".to_string())
+        );
+    }
+
+    #[test]
+    fn test_disjunctive_normal_form_canonical() {
+        assert_eq!(
+            disjunctive_normal_form::canonical_dnf("AB>"),
+            Ok("A!B!&A!B&AB&||".to_string())
+        );
+        assert_eq!(
+            disjunctive_normal_form::canonical_cnf("AB>"),
+            Ok("A!B|".to_string())
+        );
+        assert_eq!(
+            disjunctive_normal_form::canonical_dnf("AA!&"),
+            Ok("0".to_string())
+        );
+        assert_eq!(
+            disjunctive_normal_form::canonical_cnf("AA!|"),
+            Ok("1".to_string())
+        );
+        for constant in ["0", "1"] {
+            assert_eq!(
+                disjunctive_normal_form::canonical_dnf(constant),
+                Ok(constant.to_string())
+            );
+            assert_eq!(
+                disjunctive_normal_form::canonical_cnf(constant),
+                Ok(constant.to_string())
+            );
+        }
+        assert_eq!(
+            run_cli(&["dnf", "--canonical", "1"], ""),
+            (0, "1\n".to_string(), String::new())
+        );
+        for formula in ["AB^C^", "a b & c |", "A1&B|"] {
+            let dnf = disjunctive_normal_form::canonical_dnf(formula).unwrap();
+            let cnf = disjunctive_normal_form::canonical_cnf(formula).unwrap();
+            assert!(bdd::bdd_equivalent(formula, &dnf).unwrap());
+            assert!(bdd::bdd_equivalent(formula, &cnf).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_tseitin_equisatisfiable() {
+        use crate::tseitin::CnfMode;
+        for formula in ["AB^C^", "AB=A!B^&", "AB>C=", "AA!&B|", "AB&C|!D>", "AA^"] {
+            for mode in [CnfMode::Tseitin, CnfMode::PlaistedGreenbaum] {
+                let cnf =
+                    conjunctive_normal_form::conjunctive_normal_form_with(formula, mode).unwrap();
+                assert_eq!(sat::sat(&cnf), sat::sat(formula));
+            }
+            let tseitin =
+                conjunctive_normal_form::conjunctive_normal_form_with(formula, CnfMode::Tseitin)
+                    .unwrap();
+            let projected = sat::models(&tseitin)
+                .unwrap()
+                .map(|m| m.into_iter().filter(|(k, _)| !k.starts_with('_')).collect())
+                .collect::<std::collections::BTreeSet<std::collections::BTreeMap<_, _>>>();
+            assert_eq!(
+                Some(projected.len() as u128),
+                sat::count_models(formula).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_dimacs_parse_dimacs() {
+        let input = "c example\nc 2 req_valid\np cnf 3 2\n1 -2 0\n2 3\n-1 0\n";
+        let (table, clauses) = dimacs::parse_dimacs(input).unwrap();
+        assert_eq!(
+            table.names().collect::<Vec<_>>(),
+            vec!["x1", "req_valid", "x3"]
+        );
+        assert_eq!(
+            clauses,
+            vec![
+                vec![cdcl::Lit::new(0, true), cdcl::Lit::new(1, false)],
+                vec![
+                    cdcl::Lit::new(1, true),
+                    cdcl::Lit::new(2, true),
+                    cdcl::Lit::new(0, false)
+                ],
+            ]
+        );
+        assert_eq!(
+            dimacs::dimacs_to_formula(input),
+            Ok("x1 req_valid!|req_valid x3 x1!||&".to_string())
+        );
+        assert_eq!(
+            dimacs::parse_dimacs("1 2 0\n").unwrap_err(),
+            dimacs::DimacsError::MissingHeader
+        );
+        assert_eq!(
+            dimacs::parse_dimacs("p cnf 2 1\n1 3 0\n").unwrap_err(),
+            dimacs::DimacsError::VariableOutOfRange {
+                line: 2,
+                var: 3,
+                declared: 2
+            }
+        );
+        assert_eq!(
+            dimacs::parse_dimacs("p cnf 2 2\n1 a 0\n").unwrap_err(),
+            dimacs::DimacsError::InvalidLiteral {
+                line: 2,
+                token: "a".to_string()
+            }
+        );
+        assert_eq!(
+            dimacs::parse_dimacs("p cnf 2 2\n1 -2 0\n").unwrap_err(),
+            dimacs::DimacsError::ClauseCountMismatch {
+                declared: 2,
+                found: 1
+            }
+        );
+        assert_eq!(
+            dimacs::parse_dimacs("p cnf 2 1\n1 -2\n").unwrap_err(),
+            dimacs::DimacsError::UnterminatedClause
+        );
+        for declared in [(1usize << 31) + 1, 1 << 32] {
+            assert_eq!(
+                dimacs::parse_dimacs(&format!("c big\np cnf {} 0\n", declared)).unwrap_err(),
+                dimacs::DimacsError::TooManyVariables { line: 2, declared }
+            );
+        }
+        let (table, clauses) = dimacs::parse_dimacs("p cnf 2147483648 1\n1 -2 0\n").unwrap();
+        assert_eq!(table.names().collect::<Vec<_>>(), vec!["x1", "x2"]);
+        assert_eq!(
+            clauses,
+            vec![vec![cdcl::Lit::new(0, true), cdcl::Lit::new(1, false)]]
+        );
+        assert_eq!(
+            dimacs::parse_dimacs("p cnf 2 1\n1 -4294967297 0\n").unwrap_err(),
+            dimacs::DimacsError::VariableOutOfRange {
+                line: 2,
+                var: 4294967297,
+                declared: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_dimacs_formula_to_dimacs() {
+        use crate::tseitin::CnfMode;
+        assert_eq!(
+            dimacs::formula_to_dimacs("AB|C&", CnfMode::Equivalent),
+            Ok("c 1 A\nc 2 B\nc 3 C\np cnf 3 2\n1 2 0\n3 0\n".to_string())
+        );
+        for formula in ["AB^C^", "req ack> ack!&", "AA!&"] {
+            let dimacs = dimacs::formula_to_dimacs(formula, CnfMode::Tseitin).unwrap();
+            let round_trip = dimacs::dimacs_to_formula(&dimacs).unwrap();
+            assert_eq!(sat::sat(&round_trip), sat::sat(formula));
+        }
+    }
+
+    #[test]
+    fn test_set_evaluation_eval_named_set() {
+        use std::collections::BTreeMap;
+        let sets = BTreeMap::from([
+            ("evens".to_string(), vec![0, 2, 4]),
+            ("small".to_string(), vec![0, 1, 2]),
+        ]);
+        assert_eq!(
+            set_evaluation::eval_named_set("evens small&", &sets),
+            Ok(vec![0, 2])
+        );
+        assert_eq!(
+            set_evaluation::eval_named_set("evens odds&", &sets),
+            Err(set_evaluation::SetError::Parse(
+                boolean_evaluation::ParseError::UnboundVariable {
+                    name: "odds".to_string()
+                }
+            ))
         );
     }
 
     #[test]
     fn test_set_evaluation_eval_set() {
         let sets = vec![vec![0, 1, 2], vec![0, 3, 4]];
-        assert_eq!(set_evaluation::eval_set("AB&", sets), vec![0]);
+        assert_eq!(set_evaluation::eval_set("AB&", sets), Ok(vec![0]));
+    }
+
+    #[test]
+    fn test_set_evaluation_errors() {
+        use set_evaluation::SetError;
+        use std::collections::BTreeMap;
+        assert_eq!(
+            set_evaluation::eval_set("AC|", vec![vec![1], vec![2]]),
+            Err(SetError::MissingSet {
+                offset: 1,
+                symbol: 'C',
+                count: 2
+            })
+        );
+        assert_eq!(
+            set_evaluation::eval_set("AB&", vec![vec![1, 2], vec![3, 4, 3]]),
+            Err(SetError::DuplicateElement {
+                set: "B".to_string(),
+                position: 2
+            })
+        );
+        let sets = BTreeMap::from([("xs".to_string(), vec!["a", "a"])]);
+        assert_eq!(
+            set_evaluation::eval_named_set("xs!", &sets),
+            Err(SetError::DuplicateElement {
+                set: "xs".to_string(),
+                position: 1
+            })
+        );
+        assert_eq!(
+            run_cli(&["sets", "AB|", "1,2"], ""),
+            (
+                1,
+                String::new(),
+                "error: no set given for variable 'B' at offset 1 (1 sets provided)\n    AB|\n     ^\n"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_set_evaluation_generic() {
+        use set_evaluation::Backend;
+        let words = vec![vec!["pear", "apple", "fig"], vec!["fig", "kiwi"]];
+        assert_eq!(
+            set_evaluation::eval_set("AB!&", words),
+            Ok(vec!["apple", "pear"])
+        );
+
+        let sets: Vec<Vec<u32>> = (1..4)
+            .map(|k| (0..300).filter(|n| n % (k + 1) == 0).collect())
+            .collect();
+        for formula in ["AB&C|", "AB|!C&", "A!B!|C!&", "1A!&B|", "AB&!0|"] {
+            let results: Vec<_> = [Backend::SortedVec, Backend::HashSet, Backend::BitSet]
+                .into_iter()
+                .map(|backend| set_evaluation::eval_set_with(formula, sets.clone(), backend))
+                .collect();
+            assert!(results.iter().all(|r| *r == results[0]));
+        }
+        assert_eq!(Backend::for_universe(10), Backend::SortedVec);
+        assert_eq!(Backend::for_universe(1 << 20), Backend::BitSet);
+
+        let evens: Vec<u64> = (0..1_000_000).map(|n| n * 2).collect();
+        let threes: Vec<u64> = (0..1_000_000).map(|n| n * 3).collect();
+        let both = set_evaluation::eval_set("AB&", vec![evens, threes]).unwrap();
+        assert_eq!(both.len(), 333_334);
+        assert!(both.iter().all(|n| n % 6 == 0));
+    }
+
+    #[test]
+    fn test_set_evaluation_universe() {
+        use set_evaluation::{Backend, SetError, Universe};
+        let universe = Universe::new(0..10);
+        let sets = vec![vec![1, 2], vec![2, 3]];
+        assert_eq!(
+            set_evaluation::eval_set_in("A!", &sets, &universe),
+            Ok(vec![0, 3, 4, 5, 6, 7, 8, 9])
+        );
+        assert_eq!(set_evaluation::eval_set("A!", sets.clone()), Ok(vec![3]));
+        assert_eq!(
+            set_evaluation::eval_set_in("A", &[vec![1, 42]], &Universe::new(0..10)),
+            Err(SetError::UnknownElement {
+                set: "A".to_string(),
+                position: 1
+            })
+        );
+        #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        struct Opaque(u8);
+        assert_eq!(
+            set_evaluation::eval_set("AB|", vec![vec![Opaque(1)], vec![Opaque(2)]])
+                .map(|s| s.len()),
+            Ok(2)
+        );
+        let named = std::collections::BTreeMap::from([("odd".to_string(), vec![1, 3, 5])]);
+        assert_eq!(
+            set_evaluation::eval_named_set_in("odd!", &named, &Universe::new(0..6)),
+            Ok(vec![0, 2, 4])
+        );
+        assert_eq!(
+            set_evaluation::eval_named_set_in("odd!", &named, &Universe::new(0..4)),
+            Err(SetError::UnknownElement {
+                set: "odd".to_string(),
+                position: 2
+            })
+        );
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (1..9)
+                .map(|n| {
+                    scope.spawn(move || {
+                        let backend =
+                            [Backend::SortedVec, Backend::HashSet, Backend::BitSet][n % 3];
+                        let universe = Universe::with_backend(0..n * 100, backend);
+                        (0..50)
+                            .map(|_| set_evaluation::eval_set_in("A!", &[vec![0]], &universe))
+                            .all(|r| r.map(|s| s.len()) == Ok(n * 100 - 1))
+                    })
+                })
+                .collect();
+            assert!(handles.into_iter().all(|h| h.join().unwrap()));
+        });
+
+        assert_eq!(
+            run_cli(&["sets", "A!", "1,2", "--universe", "1,2,3,4"], ""),
+            (0, "[3, 4]\n".to_string(), String::new())
+        );
+        assert_eq!(
+            run_cli(&["sets", "A!", "1,5", "--universe", "1,2,3,4"], ""),
+            (
+                1,
+                String::new(),
+                "error: set 'A' has an element outside the universe at position 1\n".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_set_evaluation_operators() {
+        use set_evaluation::{eval_named_set, eval_set, eval_set_with, Backend};
+        use std::collections::BTreeMap;
+        let sets = vec![vec![1, 2, 3], vec![3, 4]];
+        assert_eq!(eval_set("AB^", sets.clone()), Ok(vec![1, 2, 4]));
+        assert_eq!(eval_set("AB-", sets.clone()), Ok(vec![1, 2]));
+        assert_eq!(eval_set("BA-", sets.clone()), Ok(vec![4]));
+        assert_eq!(eval_set("AB>", sets.clone()), Ok(vec![3, 4]));
+        assert_eq!(eval_set("AB=", sets.clone()), Ok(vec![3]));
+        assert_eq!(eval_set("A1-", sets.clone()), Ok(vec![]));
+        assert_eq!(
+            eval_set("AB^", vec![vec![1], vec![1, 2]]),
+            eval_set("AB!&A!B&|", vec![vec![1], vec![1, 2]])
+        );
+        for backend in [Backend::SortedVec, Backend::HashSet, Backend::BitSet] {
+            assert_eq!(eval_set_with("AB=A-", sets.clone(), backend), Ok(vec![]));
+            assert_eq!(eval_set_with("AB>!", sets.clone(), backend), Ok(vec![1, 2]));
+        }
+
+        let sets: Vec<Vec<u32>> = (0..26).map(|i| vec![i, i + 1]).collect();
+        let formula = ('A'..='Z').collect::<String>() + &"^".repeat(25);
+        assert_eq!(eval_set(&formula, sets.clone()), Ok(vec![0, 26]));
+        let formula = ('A'..='Z').collect::<String>() + &"=".repeat(25);
+        assert_eq!(eval_set(&formula, sets), Ok((1..26).collect()));
+
+        let named = BTreeMap::from([
+            ("all".to_string(), vec![1, 2, 3]),
+            ("odd".to_string(), vec![1, 3]),
+        ]);
+        assert_eq!(eval_named_set("all odd -", &named), Ok(vec![2]));
+        assert_eq!(
+            run_cli(&["sets", "AB-", "1,2,3", "2"], ""),
+            (0, "[1, 3]\n".to_string(), String::new())
+        );
+        assert!(boolean_evaluation::eval_formula("AB-").is_err());
+    }
+
+    #[test]
+    fn test_set_evaluation_multiset() {
+        use boolean_evaluation::ParseError;
+        use set_evaluation::{eval_multiset, eval_named_multiset, SetError};
+        use std::collections::BTreeMap;
+        let bags = vec![vec!["a", "a", "b"], vec!["a", "c", "c"]];
+        assert_eq!(eval_multiset("AB&", bags.clone()), Ok(vec![("a", 1)]));
+        assert_eq!(
+            eval_multiset("AB|", bags.clone()),
+            Ok(vec![("a", 2), ("b", 1), ("c", 2)])
+        );
+        assert_eq!(
+            eval_multiset("AB+", bags.clone()),
+            Ok(vec![("a", 3), ("b", 1), ("c", 2)])
+        );
+        assert_eq!(
+            eval_multiset("AB-", bags.clone()),
+            Ok(vec![("a", 1), ("b", 1)])
+        );
+        assert_eq!(
+            eval_multiset("AB^", bags.clone()),
+            Ok(vec![("a", 1), ("b", 1), ("c", 2)])
+        );
+        assert_eq!(eval_multiset("A!", bags.clone()), Ok(vec![("c", 2)]));
+        assert_eq!(
+            eval_multiset("AB>", bags),
+            Err(SetError::Parse(ParseError::UnknownSymbol {
+                offset: 2,
+                symbol: '>'
+            }))
+        );
+
+        let bags = BTreeMap::from([
+            ("seen".to_string(), vec![1, 1, 2]),
+            ("new".to_string(), vec![2, 3]),
+        ]);
+        assert_eq!(
+            eval_named_multiset("seen new +", &bags),
+            Ok(vec![(1, 2), (2, 2), (3, 1)])
+        );
+    }
+
+    #[test]
+    fn test_set_evaluation_fuzzy() {
+        use set_evaluation::{eval_fuzzy_set, eval_named_fuzzy_set, SetError, TNorm};
+        use std::collections::BTreeMap;
+        let sets = vec![vec![("x", 0.75), ("y", 0.25)], vec![("x", 0.5), ("z", 1.0)]];
+        let eval = |formula, tnorm| eval_fuzzy_set(formula, sets.clone(), tnorm);
+        assert_eq!(eval("AB&", TNorm::Min), Ok(vec![("x", 0.5)]));
+        assert_eq!(eval("AB&", TNorm::Product), Ok(vec![("x", 0.375)]));
+        assert_eq!(eval("AB&", TNorm::Lukasiewicz), Ok(vec![("x", 0.25)]));
+        assert_eq!(
+            eval("AB|", TNorm::Product),
+            Ok(vec![("x", 0.875), ("y", 0.25), ("z", 1.0)])
+        );
+        assert_eq!(
+            eval("AB|", TNorm::Lukasiewicz),
+            Ok(vec![("x", 1.0), ("y", 0.25), ("z", 1.0)])
+        );
+        assert_eq!(eval("AA!&", TNorm::Min), Ok(vec![("x", 0.25), ("y", 0.25)]));
+        assert_eq!(eval("AA!&", TNorm::Lukasiewicz), Ok(vec![]));
+        assert_eq!(
+            eval_fuzzy_set("AB|", vec![vec![(1, 0.5)], vec![(2, 1.5)]], TNorm::Min),
+            Err(SetError::InvalidMembership {
+                set: "B".to_string(),
+                position: 0
+            })
+        );
+
+        let mut sets = BTreeMap::from([
+            ("tall".to_string(), vec![("ann", 0.75), ("bob", 0.25)]),
+            ("fast".to_string(), vec![("ann", 0.5), ("ann", 1.0)]),
+        ]);
+        assert_eq!(
+            eval_named_fuzzy_set("tall fast&", &sets, TNorm::Min),
+            Err(SetError::DuplicateElement {
+                set: "fast".to_string(),
+                position: 1
+            })
+        );
+        sets.insert("fast".to_string(), vec![("ann", 0.5)]);
+        assert_eq!(
+            eval_named_fuzzy_set("tall!", &sets, TNorm::Min),
+            Ok(vec![("ann", 0.25), ("bob", 0.75)])
+        );
+    }
+
+    fn run_cli(args: &[&str], stdin: &str) -> (i32, String, String) {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let code = cli::run(&args, &mut stdin.as_bytes(), &mut out, &mut err);
+        (
+            code,
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_cli_formulas() {
+        assert_eq!(
+            run_cli(&["eval", "10|", "10&"], ""),
+            (0, "true\nfalse\n".to_string(), String::new())
+        );
+        assert_eq!(run_cli(&["nnf", "AB&!"], "").1, "B!A!|\n");
+        assert_eq!(
+            run_cli(&["--format", "infix", "nnf", "!(A & B)"], "").1,
+            "!B | !A\n"
+        );
+        assert_eq!(run_cli(&["sat", "--count"], "AB|\nAA!&\n").1, "3\n0\n");
+        assert_eq!(run_cli(&["sets", "AB|", "1,2", "3"], "").1, "[1, 2, 3]\n");
+        assert_eq!(
+            run_cli(
+                &["min", "--format", "infix", "--dont-care", "!a & b", "a & b"],
+                ""
+            )
+            .1,
+            "b\n"
+        );
+        assert_eq!(run_cli(&["min", "--cnf", "AB&C|"], "").1, "AC|BC|&\n");
+        assert_eq!(
+            run_cli(&["eval", "a b &", "--assign", "a=1,b=0"], "").1,
+            "false\n"
+        );
+    }
+
+    #[test]
+    fn test_cli_json() {
+        assert_eq!(
+            run_cli(&["sat", "--model", "--json", "AB&"], "").1,
+            "{\"formula\": \"AB&\", \"satisfiable\": true, \"model\": {\"A\": true, \"B\": true}}\n"
+        );
+        assert_eq!(
+            run_cli(&["eval", "--json", "1&"], "").1,
+            "{\"formula\": \"1&\", \"error\": \"missing operand for '&' at offset 1\", \"offset\": 1}\n"
+        );
+    }
+
+    #[test]
+    fn test_cli_errors() {
+        let (code, out, err) = run_cli(&["nnf"], "AB&&\n");
+        assert_eq!(code, 1);
+        assert!(out.is_empty());
+        assert_eq!(
+            err,
+            "error: missing operand for '&' at offset 3\n    AB&&\n       ^\n"
+        );
+        assert_eq!(run_cli(&["bogus"], "").0, 2);
+        assert_eq!(run_cli(&["nnf", "--format", "polish"], "").0, 2);
+        assert_eq!(run_cli(&[], "").0, 2);
+    }
+
+    #[test]
+    fn test_repl_eval_line() {
+        let mut shell = repl::Repl::new(cli::Format::Rpn);
+        assert_eq!(shell.eval_line("f := a b &"), Ok("f := a b&".to_string()));
+        assert_eq!(
+            shell.eval_line("g := f ! c |"),
+            Ok("g := a b&!c|".to_string())
+        );
+        assert_eq!(shell.eval_line("1 0 |"), Ok("true".to_string()));
+        assert_eq!(
+            shell.eval_line(":sat g c ! &"),
+            Ok("a=0 b=0 c=0".to_string())
+        );
+        assert_eq!(
+            shell.eval_line(":equiv f, a b & ! !"),
+            Ok("equivalent".to_string())
+        );
+        assert_eq!(shell.eval_line(":format infix"), Ok(String::new()));
+        assert_eq!(shell.eval_line(":nnf !f"), Ok("!b | !a".to_string()));
+        assert_eq!(
+            shell.eval_line(":equiv f, a | b"),
+            Ok("not equivalent, e.g. a=0 b=1".to_string())
+        );
+        assert_eq!(
+            shell.eval_line("f &"),
+            Err(repl::ReplError::Parse {
+                formula: "f &".to_string(),
+                error: boolean_evaluation::ParseError::UnexpectedEnd { offset: 3 }
+            })
+        );
+        assert!(matches!(
+            shell.eval_line(":undef h"),
+            Err(repl::ReplError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn test_repl_complete() {
+        let names = vec!["foo".to_string(), "bar".to_string()];
+        assert_eq!(
+            repl::complete(&names, "¬fo", 4),
+            (2, vec!["foo".to_string()])
+        );
+        assert_eq!(
+            repl::complete(&names, "a ∧ b", "a ∧ b".len()),
+            ("a ∧ ".len(), vec!["bar".to_string()])
+        );
+        assert_eq!(
+            repl::complete(&names, ":eq", 3),
+            (0, vec![":equiv".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_constant_propagation() {
+        assert_eq!(
+            negation_normal_form::negation_normal_form("A1&"),
+            Ok("A".to_string())
+        );
+        assert_eq!(
+            negation_normal_form::negation_normal_form("A0|!"),
+            Ok("A!".to_string())
+        );
+        assert_eq!(
+            negation_normal_form::negation_normal_form("1!"),
+            Ok("0".to_string())
+        );
+        assert_eq!(
+            conjunctive_normal_form::conjunctive_normal_form("AB|1>"),
+            Ok("1".to_string())
+        );
+        assert_eq!(
+            conjunctive_normal_form::conjunctive_normal_form("AB|0>"),
+            Ok("B!A!&".to_string())
+        );
+        assert_eq!(sat::sat("A0&"), Ok(false));
+        assert_eq!(sat::count_models("AB1|&"), Ok(Some(2)));
+        assert_eq!(
+            set_evaluation::eval_set("A1&", vec![vec![1, 2]]),
+            Ok(vec![1, 2])
+        );
+        assert_eq!(
+            set_evaluation::eval_set("A!0|", vec![vec![1], vec![2]]),
+            Ok(vec![2])
+        );
+        assert!(truth_table::create_dict("A1&").contains_key("A"));
+    }
+
+    #[test]
+    fn test_simplify() {
+        assert_eq!(simplify::simplify("AA|"), Ok("A".to_string()));
+        assert_eq!(simplify::simplify("AA!|"), Ok("1".to_string()));
+        assert_eq!(simplify::simplify("AA!&B|"), Ok("B".to_string()));
+        assert_eq!(simplify::simplify("AAB|&"), Ok("A".to_string()));
+        assert_eq!(simplify::simplify("AB&A|"), Ok("A".to_string()));
+        assert_eq!(simplify::simplify("A!!"), Ok("A".to_string()));
+        assert_eq!(simplify::simplify("AB|BA|&C&"), Ok("CAB|&".to_string()));
+        assert_eq!(simplify::simplify("AB|ABC||&"), Ok("BA|".to_string()));
+
+        for formula in ["AB|C&A=", "AB&AC&|A|", "AB^C>!", "AB=C|!D&"] {
+            let cnf = conjunctive_normal_form::conjunctive_normal_form(formula).unwrap();
+            let simplified = simplify::simplify(&cnf).unwrap();
+            assert!(simplified.len() <= cnf.len());
+            assert!(equivalence::equivalent(formula, &simplified)
+                .unwrap()
+                .holds());
+        }
+        assert_eq!(
+            run_cli(&["cnf", "--simplify", "AB&AC&|A|"], ""),
+            (0, "A\n".to_string(), String::new())
+        );
+    }
+
+    #[test]
+    fn test_dag() {
+        let mut table = symbol_table::SymbolTable::new();
+        let mut graph = dag::Dag::new();
+        let a = symbol_table::build_named_ast("AB&C|", &mut table).unwrap();
+        let b = symbol_table::build_named_ast("AB&C|!!", &mut table).unwrap();
+        let (a, b) = (graph.from_ast(&a), graph.from_ast(&b));
+        assert_ne!(a, b);
+        assert_eq!(graph.nnf(a), graph.nnf(b));
+        assert_eq!(graph.size(b), 7);
+
+        let mut formula = "A".to_string();
+        for i in 0..40 {
+            formula.push_str(&format!(" x{}=", i));
+        }
+        let tree = symbol_table::build_named_ast(&formula, &mut table).unwrap();
+        let root = graph.from_ast(&tree);
+        let nnf = graph.nnf(root);
+        assert!(graph.size(nnf) < 400);
+        let mut table = symbol_table::SymbolTable::new();
+        let tree = symbol_table::build_named_ast("AB=C^!", &mut table).unwrap();
+        let root = graph.from_ast(&tree);
+        let nnf = graph.nnf(root);
+        assert_eq!(
+            negation_normal_form::negation_normal_form("AB=C^!"),
+            Ok(negation_normal_form::tree_to_string(
+                &table.resolve(&graph.to_ast(nnf))
+            ))
+        );
+    }
+
+    #[test]
+    fn test_deep_formulas() {
+        let alternating = format!("1{}", "0|!".repeat(333_334));
+        assert_eq!(boolean_evaluation::eval_formula(&alternating), Ok(true));
+
+        let chain = format!("A{}", "B|".repeat(500_000));
+        let infix = infix::rpn_to_infix(&chain).unwrap();
+        assert_eq!(infix.len(), 1 + 4 * 500_000);
+        assert_eq!(infix::infix_to_rpn(&infix), Ok(chain));
+    }
+
+    #[test]
+    fn test_deep_infix() {
+        let n = 50_000;
+        let implications = format!("{}A", "A -> ".repeat(n));
+        assert_eq!(
+            infix::infix_to_rpn(&implications),
+            Ok(format!("{}{}", "A".repeat(n + 1), ">".repeat(n)))
+        );
+        let negations = format!("{}A", "!".repeat(n));
+        assert_eq!(
+            infix::infix_to_rpn(&negations),
+            Ok(format!("A{}", "!".repeat(n)))
+        );
+        let nested = format!("{}A{}", "(".repeat(n), ")".repeat(n));
+        assert_eq!(infix::infix_to_rpn(&nested), Ok("A".to_string()));
+        assert_eq!(
+            infix::infix_to_rpn(&nested[1..]),
+            Err(boolean_evaluation::ParseError::UnexpectedSymbol {
+                offset: 2 * n - 1,
+                symbol: ')'
+            })
+        );
+    }
+
+    #[test]
+    fn test_deep_many_variables() {
+        use crate::tseitin::CnfMode;
+        let n = 50_000;
+        let chain = (1..n).fold("x0".to_string(), |f, i| f + &format!(" x{}&", i));
+        let nested = (0..n).map(|i| format!("x{} ", i)).collect::<String>() + &"&".repeat(n - 1);
+        assert_eq!(sat::sat(&chain), Ok(true));
+        assert_eq!(sat::sat(&format!("{} x7!&", chain)), Ok(false));
+        let cnf = conjunctive_normal_form::conjunctive_normal_form_with(&chain, CnfMode::Tseitin)
+            .unwrap();
+        assert_eq!(cnf.matches('&').count(), 3 * (n - 1));
+        assert_eq!(
+            simplify::simplify(&chain).unwrap().matches('&').count(),
+            n - 1
+        );
+        assert_eq!(
+            equivalence::equivalent(&chain, &nested),
+            Ok(equivalence::Check::Holds)
+        );
+    }
+
+    #[test]
+    fn test_deep_normal_forms() {
+        let negations = format!("A{}", "!".repeat(1_000_000));
+        assert_eq!(
+            negation_normal_form::negation_normal_form(&negations),
+            Ok("A".to_string())
+        );
+
+        let alternating = format!("A{}", "B&!".repeat(333_334));
+        let nnf = negation_normal_form::negation_normal_form(&alternating).unwrap();
+        assert!(nnf.starts_with("B!BB!B"));
+        assert_eq!(nnf.matches('!').count(), 166_667);
+
+        let chain = format!("A{}", "B|".repeat(500_000));
+        let cnf = conjunctive_normal_form::conjunctive_normal_form(&chain).unwrap();
+        assert_eq!(cnf.matches('|').count(), 500_000);
+    }
+
+    #[test]
+    fn test_bytecode() {
+        let compile = |formula: &str| {
+            let mut table = symbol_table::SymbolTable::new();
+            let tree = symbol_table::build_named_ast(formula, &mut table).unwrap();
+            bytecode::Program::compile(&tree, &table).unwrap()
+        };
+        assert_eq!(compile("AB&AB&|").code().len(), 4);
+        assert_eq!(compile("1").rows().collect::<Vec<_>>(), vec![true]);
+        assert_eq!(
+            compile("AB>").rows().collect::<Vec<_>>(),
+            vec![true, true, false, true]
+        );
+
+        let formula = "x0 x1& x2 x3^| x4 x5>= x6!x7|&";
+        let program = compile(formula);
+        let names: Vec<String> = (0..8).map(|i| format!("x{}", i)).collect();
+        for (result, i) in program.rows().zip(0..) {
+            let assignment = names.iter().cloned().zip(program.row(i)).collect();
+            assert_eq!(
+                boolean_evaluation::eval_formula_with(formula, &assignment),
+                Ok(result)
+            );
+        }
+
+        let mut parity = "x0".to_string();
+        for i in 1..10 {
+            parity.push_str(&format!(" x{}^", i));
+        }
+        assert_eq!(compile(&parity).count(), 512);
+        assert_eq!(compile(&parity).first_model(), Some(1));
+        assert_eq!(compile("AA!&").first_model(), None);
+        assert_eq!(sat::count_models(&parity), Ok(Some(512)));
+    }
+
+    #[test]
+    fn test_truth_table_formats() {
+        use truth_table::{TableFormat, TruthTable};
+        let table = TruthTable::from_formula("AB&").unwrap();
+        assert_eq!(
+            table.render(TableFormat::Markdown),
+            truth_table::format_truth_table("AB&").unwrap()
+        );
+        assert_eq!(table.to_csv(), "A,B,=\n0,0,0\n0,1,0\n1,0,0\n1,1,1\n");
+        assert_eq!(table.to_hex(), "8");
+        assert!(table
+            .to_json()
+            .starts_with("{\"variables\": [\"A\", \"B\"], \"rows\": [{\"values\": [false, false]"));
+        assert!(table.to_latex().starts_with("\\begin{tabular}{cc|c}\n"));
+        assert_eq!(
+            TruthTable::new(vec!["A".to_string()], [false, true]),
+            TruthTable::from_formula("A")
+        );
+
+        for formula in ["AB^C|", "AB=C>D&", "x0 x1| x2 x3&^ x4 x5>= x6!|"] {
+            let table = TruthTable::from_formula(formula).unwrap();
+            let hex = table.to_hex();
+            let parsed = TruthTable::from_hex(table.variables().to_vec(), &hex).unwrap();
+            assert_eq!(parsed, table);
+            for form in [minimize::Form::Dnf, minimize::Form::Cnf] {
+                let rebuilt = parsed.to_formula(form);
+                assert!(equivalence::equivalent(formula, &rebuilt).unwrap().holds());
+            }
+        }
+        let vars = vec!["A".to_string(), "B".to_string()];
+        assert_eq!(
+            TruthTable::from_hex(vars.clone(), "0x8").map(|t| t.to_formula(minimize::Form::Dnf)),
+            Ok("AB&".to_string())
+        );
+        assert!(TruthTable::from_hex(vars.clone(), "1f").is_err());
+        assert!(TruthTable::from_hex(vars.clone(), "g").is_err());
+        assert_eq!(
+            TruthTable::from_hex(vars, "0xé"),
+            Err(boolean_evaluation::ParseError::UnknownSymbol {
+                offset: 2,
+                symbol: 'é'
+            })
+        );
+
+        use boolean_evaluation::ParseError;
+        let wide = |n: usize| (1..n).fold("x0".to_string(), |acc, i| format!("{} x{}|", acc, i));
+        let too_many = |count| ParseError::TooManyVariables {
+            count,
+            limit: bytecode::MAX_VARS,
+        };
+        assert_eq!(TruthTable::from_formula(&wide(31)), Err(too_many(31)));
+        assert_eq!(TruthTable::from_formula(&wide(64)), Err(too_many(64)));
+        let names: Vec<String> = (0..64).map(|i| format!("x{}", i)).collect();
+        assert_eq!(TruthTable::from_hex(names.clone(), "1"), Err(too_many(64)));
+        assert_eq!(TruthTable::new(names, [true]), Err(too_many(64)));
+        assert_eq!(
+            disjunctive_normal_form::canonical_dnf(&wide(40)),
+            Err(too_many(40))
+        );
+        assert_eq!(
+            minimize::minimize(&wide(40), None, minimize::Form::Dnf),
+            Err(too_many(40))
+        );
+        assert_eq!(sat::count_models(&wide(40)), Ok(Some((1 << 40) - 1)));
+        assert_eq!(
+            run_cli(&["table", &wide(64)], "").2,
+            "error: 64 variables is more than the 30 a truth table can enumerate\n"
+        );
+        assert_eq!(
+            run_cli(&["table", "--table-format", "hex", "AB|", "ABC^^"], ""),
+            (0, "e\n96\n".to_string(), String::new())
+        );
     }
 
     // For helper functions like tree_to_string, tree_to_almost_nnf, etc., perhaps skip or test indirectly
@@ -1,31 +1,51 @@
-use crate::boolean_evaluation::build_ast;
-use crate::boolean_evaluation::eval_node;
+use crate::boolean_evaluation::ParseError;
+use crate::bytecode::{check_vars, Program};
+use crate::minimize::{cover_to_rpn, minimize_table, Form};
+use crate::symbol_table::{build_named_ast, scan_name, SymbolTable};
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 
-pub fn substitute_vars(formula: &str, dict: &BTreeMap<char, bool>) -> String {
-    let mut new_formula = formula.to_string();
-    for (key, value) in dict.iter() {
-        let replacement = if *value { '1' } else { '0' };
-        new_formula = new_formula.replace(*key, &replacement.to_string());
+pub fn substitute_vars<K: ToString>(formula: &str, dict: &BTreeMap<K, bool>) -> String {
+    let values: HashMap<String, bool> = dict.iter().map(|(k, v)| (k.to_string(), *v)).collect();
+    let chars: Vec<char> = formula.chars().collect();
+    let mut new_formula = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let end = scan_name(&chars, i);
+        if end == i {
+            new_formula.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let name: String = chars[i..end].iter().collect();
+        match values.get(&name) {
+            Some(value) => new_formula.push(if *value { '1' } else { '0' }),
+            None => new_formula.push_str(&name),
+        }
+        i = end;
     }
     new_formula
 }
 
-pub fn create_dict(formula: &str) -> BTreeMap<char, bool> {
-    let mut dict: BTreeMap<char, bool> = BTreeMap::new();
-    for c in formula.chars() {
-        if c >= 'A' && c <= 'Z' {
-            dict.insert(c, false);
-        }
-        if c == '0' || c == '1' {
-            println!("Formula contains constants");
-            return BTreeMap::new();
+pub fn create_dict(formula: &str) -> BTreeMap<String, bool> {
+    let mut dict: BTreeMap<String, bool> = BTreeMap::new();
+    let chars: Vec<char> = formula.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let end = scan_name(&chars, i);
+        if end > i {
+            dict.insert(chars[i..end].iter().collect(), false);
+            i = end;
+            continue;
         }
+        i += 1;
     }
     dict
 }
 
-pub fn update_dict(dict: &mut BTreeMap<char, bool>, i: i32) {
+pub fn update_dict<K>(dict: &mut BTreeMap<K, bool>, i: i32) {
     let mut j = i;
     for val in dict.values_mut().rev() {
         *val = (j & 1) == 1;
@@ -33,33 +53,223 @@ pub fn update_dict(dict: &mut BTreeMap<char, bool>, i: i32) {
     }
 }
 
-pub fn print_truth_table(formula: &str) {
-    if build_ast(formula).is_none() {
-        println!("Error in formula");
-        return;
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TableFormat {
+    #[default]
+    Markdown,
+    Csv,
+    Json,
+    Latex,
+    Hex,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TruthTable {
+    variables: Vec<String>,
+    outputs: Vec<u64>,
+}
+
+impl TruthTable {
+    pub fn new(
+        variables: Vec<String>,
+        outputs: impl IntoIterator<Item = bool>,
+    ) -> Result<TruthTable, ParseError> {
+        check_vars(variables.len())?;
+        let rows = 1usize << variables.len();
+        let mut words = vec![0; rows.div_ceil(64)];
+        for (row, output) in outputs.into_iter().take(rows).enumerate() {
+            if output {
+                words[row / 64] |= 1 << (row % 64);
+            }
+        }
+        Ok(TruthTable {
+            variables,
+            outputs: words,
+        })
+    }
+
+    pub fn from_formula(formula: &str) -> Result<TruthTable, ParseError> {
+        let mut table = SymbolTable::new();
+        let program = Program::compile(&build_named_ast(formula, &mut table)?, &table)?;
+        let mut variables: Vec<String> = table.names().map(|n| n.to_string()).collect();
+        variables.sort();
+        Ok(TruthTable {
+            variables,
+            outputs: program.blocks().collect(),
+        })
+    }
+
+    pub fn from_hex(variables: Vec<String>, hex: &str) -> Result<TruthTable, ParseError> {
+        let digits = hex.strip_prefix("0x").unwrap_or(hex);
+        let skipped = hex.chars().count() - digits.chars().count();
+        check_vars(variables.len())?;
+        let rows = 1usize << variables.len();
+        let mut outputs = vec![false; rows];
+        let digits: Vec<(usize, char)> = digits.chars().enumerate().collect();
+        for (k, &(offset, c)) in digits.iter().rev().enumerate() {
+            let offset = skipped + offset;
+            let Some(nibble) = c.to_digit(16) else {
+                return Err(ParseError::UnknownSymbol { offset, symbol: c });
+            };
+            for bit in 0..4 {
+                if (nibble >> bit) & 1 == 0 {
+                    continue;
+                }
+                match outputs.get_mut(4 * k + bit) {
+                    Some(output) => *output = true,
+                    None => return Err(ParseError::UnexpectedSymbol { offset, symbol: c }),
+                }
+            }
+        }
+        TruthTable::new(variables, outputs)
     }
 
-    let mut dict = create_dict(formula);
-    if dict.is_empty() {
-        println!("No variables in formula");
-        return;
+    pub fn variables(&self) -> &[String] {
+        &self.variables
     }
 
-    for key in dict.keys() {
-        print!("| {} ", key);
+    pub fn row_count(&self) -> usize {
+        1 << self.variables.len()
     }
-    println!("| = |");
-    println!("{}|", "|---".repeat(dict.len() + 1));
 
-    for i in 0..(1 << dict.len()) {
-        update_dict(&mut dict, i);
+    pub fn row(&self, row: usize) -> Vec<bool> {
+        let vars = self.variables.len();
+        (0..vars)
+            .map(|k| (row >> (vars - 1 - k)) & 1 == 1)
+            .collect()
+    }
 
-        let new_formula = substitute_vars(&formula, &dict);
-        let tree = build_ast(&new_formula).unwrap();
+    pub fn output(&self, row: usize) -> bool {
+        (self.outputs[row / 64] >> (row % 64)) & 1 == 1
+    }
 
-        for value in dict.values() {
-            print!("| {} ", if *value { '1' } else { '0' });
+    pub fn outputs(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.row_count()).map(|row| self.output(row))
+    }
+
+    pub fn render(&self, format: TableFormat) -> String {
+        match format {
+            TableFormat::Markdown => self.to_markdown(),
+            TableFormat::Csv => self.to_csv(),
+            TableFormat::Json => self.to_json(),
+            TableFormat::Latex => self.to_latex(),
+            TableFormat::Hex => self.to_hex(),
         }
-        println!("| {} |", if eval_node(&tree).unwrap() { '1' } else { '0' });
     }
+
+    pub fn to_markdown(&self) -> String {
+        let mut res = String::new();
+        for name in self.variables.iter() {
+            res.push_str(&format!("| {} ", name));
+        }
+        res.push_str("| = |\n");
+        for name in self.variables.iter() {
+            res.push_str(&format!("|{}", "-".repeat(name.chars().count() + 2)));
+        }
+        res.push_str("|---|\n");
+
+        for (i, result) in self.outputs().enumerate() {
+            for (name, value) in self.variables.iter().zip(self.row(i)) {
+                res.push_str(&format!(
+                    "| {:^w$} ",
+                    if value { '1' } else { '0' },
+                    w = name.chars().count()
+                ));
+            }
+            res.push_str(&format!("| {} |\n", if result { '1' } else { '0' }));
+        }
+        res
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut res = String::new();
+        for name in self.variables.iter() {
+            res.push_str(&format!("{},", name));
+        }
+        res.push_str("=\n");
+        for (i, result) in self.outputs().enumerate() {
+            for value in self.row(i) {
+                res.push_str(if value { "1," } else { "0," });
+            }
+            res.push_str(if result { "1\n" } else { "0\n" });
+        }
+        res
+    }
+
+    pub fn to_json(&self) -> String {
+        let names: Vec<String> = self
+            .variables
+            .iter()
+            .map(|n| format!("\"{}\"", n))
+            .collect();
+        let rows: Vec<String> = self
+            .outputs()
+            .enumerate()
+            .map(|(i, result)| {
+                let values: Vec<String> = self.row(i).iter().map(|v| v.to_string()).collect();
+                format!(
+                    "{{\"values\": [{}], \"result\": {}}}",
+                    values.join(", "),
+                    result
+                )
+            })
+            .collect();
+        format!(
+            "{{\"variables\": [{}], \"rows\": [{}]}}",
+            names.join(", "),
+            rows.join(", ")
+        )
+    }
+
+    pub fn to_latex(&self) -> String {
+        let mut res = format!(
+            "\\begin{{tabular}}{{{}|c}}\n",
+            "c".repeat(self.variables.len())
+        );
+        for name in self.variables.iter() {
+            res.push_str(&format!("\\texttt{{{}}} & ", name.replace('_', "\\_")));
+        }
+        res.push_str("$f$ \\\\\n\\hline\n");
+        for (i, result) in self.outputs().enumerate() {
+            for value in self.row(i) {
+                res.push_str(if value { "1 & " } else { "0 & " });
+            }
+            res.push_str(if result { "1 \\\\\n" } else { "0 \\\\\n" });
+        }
+        res.push_str("\\end{tabular}\n");
+        res
+    }
+
+    pub fn to_hex(&self) -> String {
+        let digits = self.row_count().div_ceil(4);
+        (0..digits)
+            .rev()
+            .map(|k| {
+                let nibble = (self.outputs[4 * k / 64] >> (4 * k % 64)) & 0xF;
+                char::from_digit(nibble as u32, 16).unwrap()
+            })
+            .collect()
+    }
+
+    pub fn to_formula(&self, form: Form) -> String {
+        let on: Vec<u64> = self
+            .outputs()
+            .zip(0..)
+            .filter_map(|(result, row)| (result == (form == Form::Dnf)).then_some(row))
+            .collect();
+        cover_to_rpn(
+            &minimize_table(self.variables.len(), &on, &[]),
+            &self.variables,
+            form,
+        )
+    }
+}
+
+pub fn format_truth_table(formula: &str) -> Result<String, ParseError> {
+    Ok(TruthTable::from_formula(formula)?.to_markdown())
+}
+
+pub fn print_truth_table(formula: &str) -> Result<(), ParseError> {
+    print!("{}", format_truth_table(formula)?);
+    Ok(())
 }
@@ -0,0 +1,166 @@
+use crate::boolean_evaluation::ASTNode;
+use crate::cdcl::Lit;
+use crate::symbol_table::{Symbol, SymbolTable};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CnfMode {
+    Equivalent,
+    Tseitin,
+    PlaistedGreenbaum,
+}
+
+#[derive(Clone, Copy)]
+struct Polarity {
+    positive: bool,
+    negative: bool,
+}
+
+impl Polarity {
+    const BOTH: Polarity = Polarity {
+        positive: true,
+        negative: true,
+    };
+
+    fn flipped(self) -> Polarity {
+        Polarity {
+            positive: self.negative,
+            negative: self.positive,
+        }
+    }
+}
+
+enum Task<'a> {
+    Visit(&'a ASTNode<Symbol>, Polarity),
+    Negate,
+    Gate(char, Polarity),
+}
+
+struct Encoder<'a> {
+    table: &'a mut SymbolTable,
+    clauses: Vec<Vec<Lit>>,
+    polarity_aware: bool,
+}
+
+impl Encoder<'_> {
+    fn define(&mut self, polarity: Polarity, implies: Vec<Vec<Lit>>, implied_by: Vec<Vec<Lit>>) {
+        if polarity.positive || !self.polarity_aware {
+            self.clauses.extend(implies);
+        }
+        if polarity.negative || !self.polarity_aware {
+            self.clauses.extend(implied_by);
+        }
+    }
+
+    fn gate(&mut self, operator: char, polarity: Polarity, a: Lit, b: Lit) -> Lit {
+        let x = Lit::new(self.table.fresh("_t"), true);
+        match operator {
+            '&' => self.define(
+                polarity,
+                vec![vec![!x, a], vec![!x, b]],
+                vec![vec![x, !a, !b]],
+            ),
+            '|' => self.define(
+                polarity,
+                vec![vec![!x, a, b]],
+                vec![vec![x, !a], vec![x, !b]],
+            ),
+            '>' => self.define(
+                polarity,
+                vec![vec![!x, !a, b]],
+                vec![vec![x, a], vec![x, !b]],
+            ),
+            '^' => self.define(
+                polarity,
+                vec![vec![!x, a, b], vec![!x, !a, !b]],
+                vec![vec![x, !a, b], vec![x, a, !b]],
+            ),
+            _ => self.define(
+                polarity,
+                vec![vec![!x, !a, b], vec![!x, a, !b]],
+                vec![vec![x, a, b], vec![x, !a, !b]],
+            ),
+        }
+        x
+    }
+
+    fn encode(&mut self, node: &ASTNode<Symbol>, polarity: Polarity) -> Lit {
+        let mut tasks = vec![Task::Visit(node, polarity)];
+        let mut lits = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(ASTNode::Value(Symbol::Var(id)), _) => lits.push(Lit::new(*id, true)),
+                Task::Visit(ASTNode::Value(constant), _) => {
+                    let x = Lit::new(self.table.fresh("_t"), true);
+                    self.clauses
+                        .push(vec![if *constant == Symbol::True { x } else { !x }]);
+                    lits.push(x);
+                }
+                Task::Visit(
+                    ASTNode::Op {
+                        operator: '!',
+                        right,
+                        ..
+                    },
+                    polarity,
+                ) => {
+                    tasks.push(Task::Negate);
+                    tasks.push(Task::Visit(right, polarity.flipped()));
+                }
+                Task::Visit(
+                    ASTNode::Op {
+                        operator,
+                        left,
+                        right,
+                    },
+                    polarity,
+                ) => {
+                    let (left_polarity, right_polarity) = match operator {
+                        '&' | '|' => (polarity, polarity),
+                        '>' => (polarity.flipped(), polarity),
+                        _ => (Polarity::BOTH, Polarity::BOTH),
+                    };
+                    tasks.push(Task::Gate(*operator, polarity));
+                    tasks.push(Task::Visit(right, right_polarity));
+                    tasks.push(Task::Visit(left.as_ref().unwrap(), left_polarity));
+                }
+                Task::Negate => {
+                    let a = lits.pop().unwrap();
+                    lits.push(!a);
+                }
+                Task::Gate(operator, polarity) => {
+                    let b = lits.pop().unwrap();
+                    let a = lits.pop().unwrap();
+                    let x = self.gate(operator, polarity, a, b);
+                    lits.push(x);
+                }
+            }
+        }
+        lits.pop().unwrap()
+    }
+}
+
+pub fn tseitin_clauses(
+    tree: &ASTNode<Symbol>,
+    table: &mut SymbolTable,
+    polarity_aware: bool,
+) -> Vec<Vec<Lit>> {
+    match tree {
+        ASTNode::Value(Symbol::True) => return Vec::new(),
+        ASTNode::Value(Symbol::False) => return vec![Vec::new()],
+        _ => {}
+    }
+    let mut encoder = Encoder {
+        table,
+        clauses: Vec::new(),
+        polarity_aware,
+    };
+    let root = encoder.encode(
+        tree,
+        Polarity {
+            positive: true,
+            negative: false,
+        },
+    );
+    encoder.clauses.push(vec![root]);
+    encoder.clauses
+}